@@ -0,0 +1,233 @@
+//! Every value the launch options of each app had in localconfig.vdf, with when and how it got
+//! there. Unlike the git history this is always kept and covers changes made outside the app.
+//! Each account has its own localconfig.vdf and so its own history.
+
+use crate::launch_options;
+use crate::schema::{self, Migration};
+use crate::team;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use strum_macros::Display;
+
+const CONFIG_NAME: &str = "app_history";
+const MIGRATIONS: &[Migration] = &[v0_to_v1];
+pub const VERSION: u32 = MIGRATIONS.len() as u32;
+/// Older values are dropped beyond this many per app.
+const MAX_ENTRIES: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Display, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    #[strum(serialize = "💾 Saved")]
+    Save,
+    /// Copied from another account or pulled from the team profile
+    #[strum(serialize = "⮊ Imported")]
+    Import,
+    /// Changed by Steam or another tool, noticed when the apps were read
+    #[strum(serialize = "⟳ External")]
+    External,
+    #[strum(serialize = "↩ Reverted")]
+    Revert,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub launch_options: String,
+    /// Seconds since the Unix epoch
+    pub time: u64,
+    pub source: Source,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AppHistory {
+    #[serde(default)]
+    pub version: u32,
+    /// Keyed by the path of the account's localconfig.vdf, then by app ID
+    #[serde(default)]
+    pub accounts: BTreeMap<String, BTreeMap<String, Vec<Entry>>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    Same,
+    Removed,
+    Added,
+}
+
+impl AppHistory {
+    /// Oldest first.
+    pub fn entries(&self, account: &str, appid: u32) -> &[Entry] {
+        self.accounts
+            .get(account)
+            .and_then(|apps| apps.get(&appid.to_string()))
+            .map_or(&[], |entries| entries.as_slice())
+    }
+
+    /// Appends the value if it differs from the last one of the app in the account. The first
+    /// value of an app is only kept when it is not empty. Returns whether it was appended.
+    pub fn record(
+        &mut self,
+        account: &str,
+        appid: u32,
+        launch_options: &str,
+        source: Source,
+        time: u64,
+    ) -> bool {
+        let apps = self.accounts.entry(account.to_string()).or_default();
+        let entries = apps.entry(appid.to_string()).or_default();
+        let changed = match entries.last() {
+            Some(last) => last.launch_options != launch_options,
+            None => !launch_options.is_empty(),
+        };
+        if changed {
+            entries.push(Entry {
+                launch_options: launch_options.to_string(),
+                time,
+                source,
+            });
+            if entries.len() > MAX_ENTRIES {
+                entries.drain(..entries.len() - MAX_ENTRIES);
+            }
+        }
+        if entries.is_empty() {
+            apps.remove(&appid.to_string());
+        }
+        if apps.is_empty() {
+            self.accounts.remove(account);
+        }
+        changed
+    }
+}
+
+/// Version 0 kept a single history for all accounts, whose values cannot be told apart, so it is
+/// dropped.
+fn v0_to_v1(table: &mut toml::Table) -> Result<(), Box<dyn Error>> {
+    table.remove("apps");
+    Ok(())
+}
+
+pub fn load() -> AppHistory {
+    schema::load(Some(CONFIG_NAME), MIGRATIONS)
+}
+
+pub fn store(history: &mut AppHistory) -> Result<(), Box<dyn Error>> {
+    history.version = VERSION;
+    schema::store(Some(CONFIG_NAME), &*history)
+}
+
+/// Records the values of several apps of the account and stores the history if any changed.
+pub fn record_all<'a>(
+    account: &str,
+    values: impl Iterator<Item = (u32, &'a str, Source)>,
+) -> Result<(), Box<dyn Error>> {
+    let mut history = load();
+    let now = team::now();
+    let mut changed = false;
+    for (appid, launch_options, source) in values {
+        changed |= history.record(account, appid, launch_options, source, now);
+    }
+    if changed {
+        store(&mut history)?;
+    }
+    Ok(())
+}
+
+/// Words of the old and new launch options, marked by whether they were kept, removed or added.
+pub fn diff(old: &str, new: &str) -> Vec<(Change, String)> {
+    let old = launch_options::split(old);
+    let new = launch_options::split(new);
+    // Longest common subsequence of the words
+    let mut lengths = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let mut result = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            result.push((Change::Same, old[i].clone()));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            result.push((Change::Removed, old[i].clone()));
+            i += 1;
+        } else {
+            result.push((Change::Added, new[j].clone()));
+            j += 1;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let a = "/steam/userdata/1/config/localconfig.vdf";
+        let b = "/steam/userdata/2/config/localconfig.vdf";
+        let mut history = AppHistory::default();
+        assert!(!history.record(a, 1111111, "", Source::External, 1));
+        assert!(history.accounts.is_empty());
+        assert!(history.record(a, 1111111, "%command% -novid", Source::External, 2));
+        assert!(!history.record(a, 1111111, "%command% -novid", Source::Save, 3));
+        // Another account does not interleave its values
+        assert!(history.record(b, 1111111, "gamemoderun %command%", Source::External, 3));
+        assert!(history.record(a, 1111111, "", Source::Save, 4));
+        assert_eq!(
+            history
+                .entries(a, 1111111)
+                .iter()
+                .map(|entry| (entry.time, entry.source))
+                .collect::<Vec<_>>(),
+            vec![(2, Source::External), (4, Source::Save)]
+        );
+        assert_eq!(history.entries(b, 1111111).len(), 1);
+        for time in 0..MAX_ENTRIES as u64 {
+            history.record(a, 2222222, &time.to_string(), Source::Save, time);
+        }
+        history.record(a, 2222222, "last", Source::Import, 1000);
+        assert_eq!(history.entries(a, 2222222).len(), MAX_ENTRIES);
+        assert_eq!(history.entries(a, 2222222)[0].launch_options, "1");
+
+        let stored: AppHistory = toml::from_str(&toml::to_string(&history).unwrap()).unwrap();
+        assert_eq!(stored.entries(a, 1111111), history.entries(a, 1111111));
+    }
+
+    #[test]
+    fn test_v0_to_v1() {
+        let mut table: toml::Table =
+            "[[apps.1111111]]\nlaunch_options = \"%command%\"\ntime = 1\nsource = \"save\""
+                .parse()
+                .unwrap();
+        schema::migrate(&mut table, MIGRATIONS).unwrap();
+        let history: AppHistory = table.try_into().unwrap();
+        assert_eq!(history.version, 1);
+        assert!(history.accounts.is_empty());
+    }
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(
+            diff(
+                "PROTON_LOG=1 %command% -novid",
+                "gamemoderun %command% -novid -high"
+            ),
+            vec![
+                (Change::Removed, "PROTON_LOG=1".to_string()),
+                (Change::Added, "gamemoderun".to_string()),
+                (Change::Same, "%command%".to_string()),
+                (Change::Same, "-novid".to_string()),
+                (Change::Added, "-high".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,307 @@
+//! Launch entries from Steam's binary `appcache/appinfo.vdf`.
+
+use log::debug;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const MAGIC_V28: u32 = 0x07564428;
+const MAGIC_V29: u32 = 0x07564429;
+
+const TYPE_SECTION: u8 = 0x00;
+const TYPE_STRING: u8 = 0x01;
+const TYPE_INT: u8 = 0x02;
+const TYPE_FLOAT: u8 = 0x03;
+const TYPE_POINTER: u8 = 0x04;
+const TYPE_COLOR: u8 = 0x06;
+const TYPE_UINT64: u8 = 0x07;
+const TYPE_END: u8 = 0x08;
+const TYPE_INT64: u8 = 0x0a;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Section(Vec<(String, Node)>),
+    String(String),
+    Number(i64),
+}
+
+impl Node {
+    fn get(&self, key: &str) -> Option<&Node> {
+        match self {
+            Node::Section(children) => children
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                .map(|(_, node)| node),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Node::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LaunchEntry {
+    /// Relative to the install directory, with `/` separators
+    pub executable: String,
+    pub arguments: String,
+    /// Comma separated, e.g. `windows` or `linux,macos`, empty for all
+    pub oslist: String,
+}
+
+impl LaunchEntry {
+    pub fn is_windows(&self) -> bool {
+        self.oslist.contains("windows")
+    }
+}
+
+fn read_u8(reader: &mut impl Read) -> Result<u8, Box<dyn Error>> {
+    let mut buffer = [0; 1];
+    reader.read_exact(&mut buffer)?;
+    Ok(buffer[0])
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, Box<dyn Error>> {
+    let mut buffer = [0; 4];
+    reader.read_exact(&mut buffer)?;
+    Ok(u32::from_le_bytes(buffer))
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64, Box<dyn Error>> {
+    let mut buffer = [0; 8];
+    reader.read_exact(&mut buffer)?;
+    Ok(u64::from_le_bytes(buffer))
+}
+
+fn read_cstring(reader: &mut impl Read) -> Result<String, Box<dyn Error>> {
+    let mut bytes = vec![];
+    loop {
+        match read_u8(reader)? {
+            0 => break,
+            byte => bytes.push(byte),
+        }
+    }
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+/// Reads a binary key values section up to its end marker. Version 29 stores keys as indices
+/// into a string table, older versions inline.
+fn read_section(
+    reader: &mut impl Read,
+    strings: &Option<Vec<String>>,
+) -> Result<Node, Box<dyn Error>> {
+    let mut children = vec![];
+    loop {
+        let kind = read_u8(reader)?;
+        if kind == TYPE_END {
+            break;
+        }
+        let key = match strings {
+            Some(strings) => {
+                let index = read_u32(reader)? as usize;
+                strings
+                    .get(index)
+                    .cloned()
+                    .ok_or(format!("invalid string index: {}", index))?
+            }
+            None => read_cstring(reader)?,
+        };
+        let node = match kind {
+            TYPE_SECTION => read_section(reader, strings)?,
+            TYPE_STRING => Node::String(read_cstring(reader)?),
+            TYPE_INT | TYPE_FLOAT | TYPE_POINTER | TYPE_COLOR => {
+                Node::Number(read_u32(reader)? as i32 as i64)
+            }
+            TYPE_UINT64 | TYPE_INT64 => Node::Number(read_u64(reader)? as i64),
+            _ => return Err(format!("unknown type: {:#x}", kind).into()),
+        };
+        children.push((key, node));
+    }
+    Ok(Node::Section(children))
+}
+
+fn launch_entries(appinfo: &Node) -> Vec<LaunchEntry> {
+    let Some(Node::Section(launch)) = appinfo
+        .get("config")
+        .and_then(|config| config.get("launch"))
+    else {
+        return vec![];
+    };
+    launch
+        .iter()
+        .map(|(_, entry)| LaunchEntry {
+            executable: entry
+                .get("executable")
+                .and_then(Node::as_str)
+                .unwrap_or_default()
+                .replace('\\', "/"),
+            arguments: entry
+                .get("arguments")
+                .and_then(Node::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            oslist: entry
+                .get("config")
+                .and_then(|config| config.get("oslist"))
+                .and_then(Node::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        })
+        .filter(|entry| !entry.executable.is_empty())
+        .collect()
+}
+
+pub fn read_launch_entries_from(
+    reader: &mut (impl Read + Seek),
+    appid: u32,
+) -> Result<Vec<LaunchEntry>, Box<dyn Error>> {
+    let magic = read_u32(reader)?;
+    let _universe = read_u32(reader)?;
+    let strings = match magic {
+        MAGIC_V29 => {
+            let offset = read_u64(reader)?;
+            let start = reader.stream_position()?;
+            reader.seek(SeekFrom::Start(offset))?;
+            let count = read_u32(reader)?;
+            let strings = (0..count)
+                .map(|_| read_cstring(reader))
+                .collect::<Result<Vec<String>, _>>()?;
+            reader.seek(SeekFrom::Start(start))?;
+            Some(strings)
+        }
+        MAGIC_V28 => None,
+        _ => return Err(format!("unsupported appinfo.vdf version: {:#x}", magic).into()),
+    };
+    loop {
+        let id = read_u32(reader)?;
+        if id == 0 {
+            return Ok(vec![]);
+        }
+        let size = read_u32(reader)?;
+        if id != appid {
+            reader.seek(SeekFrom::Current(size as i64))?;
+            continue;
+        }
+        // info state, last updated, access token, SHA-1, change number, binary SHA-1
+        reader.seek(SeekFrom::Current(4 + 4 + 8 + 20 + 4 + 20))?;
+        let node = read_section(reader, &strings)?;
+        let appinfo = node.get("appinfo").unwrap_or(&node);
+        return Ok(launch_entries(appinfo));
+    }
+}
+
+pub fn read_launch_entries(path: &Path, appid: u32) -> Result<Vec<LaunchEntry>, Box<dyn Error>> {
+    debug!(
+        "reading launch entries of {} from {}",
+        appid,
+        path.display()
+    );
+    let mut reader = BufReader::new(File::open(path)?);
+    read_launch_entries_from(&mut reader, appid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn section(bytes: &mut Vec<u8>, key: u32, children: impl FnOnce(&mut Vec<u8>)) {
+        bytes.push(TYPE_SECTION);
+        bytes.extend(key.to_le_bytes());
+        children(bytes);
+        bytes.push(TYPE_END);
+    }
+
+    fn string(bytes: &mut Vec<u8>, key: u32, value: &str) {
+        bytes.push(TYPE_STRING);
+        bytes.extend(key.to_le_bytes());
+        bytes.extend(value.as_bytes());
+        bytes.push(0);
+    }
+
+    /// A version 29 file with one app and two launch entries.
+    fn appinfo() -> Vec<u8> {
+        let strings = [
+            "appinfo",
+            "config",
+            "launch",
+            "0",
+            "1",
+            "executable",
+            "arguments",
+            "oslist",
+        ];
+        let mut data = vec![];
+        section(&mut data, 0, |bytes| {
+            section(bytes, 1, |bytes| {
+                section(bytes, 2, |bytes| {
+                    section(bytes, 3, |bytes| {
+                        string(bytes, 5, "bin\\x64\\witcher3.exe");
+                        string(bytes, 6, "-net");
+                        section(bytes, 1, |bytes| string(bytes, 7, "windows"));
+                    });
+                    section(bytes, 4, |bytes| {
+                        string(bytes, 5, "witcher3.sh");
+                        section(bytes, 1, |bytes| string(bytes, 7, "linux"));
+                    });
+                });
+            });
+        });
+        data.push(TYPE_END);
+
+        let mut bytes = vec![];
+        bytes.extend(MAGIC_V29.to_le_bytes());
+        bytes.extend(1u32.to_le_bytes());
+        let offset_position = bytes.len();
+        bytes.extend(0u64.to_le_bytes());
+        for (appid, data) in [(2222222u32, vec![TYPE_END]), (1111111, data)] {
+            bytes.extend(appid.to_le_bytes());
+            bytes.extend((60 + data.len() as u32).to_le_bytes());
+            bytes.extend([0; 60]);
+            bytes.extend(data);
+        }
+        bytes.extend(0u32.to_le_bytes());
+        let offset = bytes.len() as u64;
+        bytes[offset_position..offset_position + 8].copy_from_slice(&offset.to_le_bytes());
+        bytes.extend((strings.len() as u32).to_le_bytes());
+        for string in strings {
+            bytes.extend(string.as_bytes());
+            bytes.push(0);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_read_launch_entries() {
+        let mut reader = Cursor::new(appinfo());
+        let entries = read_launch_entries_from(&mut reader, 1111111).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                LaunchEntry {
+                    executable: "bin/x64/witcher3.exe".to_string(),
+                    arguments: "-net".to_string(),
+                    oslist: "windows".to_string(),
+                },
+                LaunchEntry {
+                    executable: "witcher3.sh".to_string(),
+                    arguments: String::new(),
+                    oslist: "linux".to_string(),
+                },
+            ]
+        );
+        assert!(entries[0].is_windows());
+
+        let mut reader = Cursor::new(appinfo());
+        assert!(
+            read_launch_entries_from(&mut reader, 3333333)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
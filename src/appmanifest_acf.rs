@@ -1,38 +1,178 @@
-use log::debug;
-use serde::Deserialize;
+use crate::consts;
+use log::{debug, warn};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const CACHE_NAME: &str = "manifests";
 
 #[derive(Deserialize, Debug, Clone)]
 struct AppState {
     name: String,
+    #[serde(default)]
+    installdir: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedManifest {
+    modified: SystemTime,
+    name: String,
+    #[serde(default)]
+    installdir: String,
 }
 
-pub fn read_app_names(
-    apps: (Vec<PathBuf>, Vec<Vec<String>>),
-) -> Result<BTreeMap<u32, String>, Box<dyn Error>> {
-    let mut result = BTreeMap::new();
-    for (i, path) in apps.0.iter().enumerate() {
-        let mut path = PathBuf::from(path.clone());
-        path.push("steamapps");
-        for appid in apps.1[i].iter() {
-            let filename = "appmanifest_".to_owned() + appid.as_str() + ".acf";
-            let mut path = PathBuf::from(path.clone());
-            path.push(filename.clone());
-            let contents = fs::read_to_string(&path);
-            let contents = match contents {
-                Ok(contents) => contents,
-                Err(_) => {
-                    debug!("error reading: {}", &path.display());
-                    continue;
+/// App names by manifest path, reused while the manifest's modification time is unchanged.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ManifestCache {
+    manifests: BTreeMap<String, CachedManifest>,
+}
+
+#[derive(Debug, Default)]
+pub struct AppNames {
+    pub names: BTreeMap<u32, String>,
+    /// Directory names under `steamapps/common`
+    pub installdirs: BTreeMap<u32, String>,
+    /// One message per manifest that could not be read
+    pub errors: Vec<String>,
+}
+
+/// Returns `None` for apps that are listed in a library but have no manifest yet.
+fn read_manifest(
+    path: &Path,
+    cached: Option<&CachedManifest>,
+) -> Result<Option<CachedManifest>, String> {
+    let modified = match fs::metadata(path).and_then(|metadata| metadata.modified()) {
+        Ok(modified) => modified,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            debug!("error reading: {}", path.display());
+            return Ok(None);
+        }
+        Err(err) => return Err(err.to_string()),
+    };
+    // Entries cached before the install directory was stored are read again
+    if let Some(cached) = cached
+        && cached.modified == modified
+        && !cached.installdir.is_empty()
+    {
+        return Ok(Some(cached.clone()));
+    }
+    debug!("parsing: {}", path.display());
+    let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let appstate: AppState =
+        keyvalues_serde::from_str(contents.as_str()).map_err(|err| err.to_string())?;
+    Ok(Some(CachedManifest {
+        modified,
+        name: appstate.name,
+        installdir: appstate.installdir,
+    }))
+}
+
+/// Reads the names of the installed apps, parsing only manifests that changed since they were
+/// cached. The cache is replaced with the manifests that were read.
+pub fn read_app_names_cached(
+    apps: &(Vec<PathBuf>, Vec<Vec<String>>),
+    cache: &mut ManifestCache,
+) -> AppNames {
+    let mut result = AppNames::default();
+    let mut manifests = vec![];
+    for (library, appids) in apps.0.iter().zip(apps.1.iter()) {
+        for appid in appids.iter() {
+            let path = library
+                .join("steamapps")
+                .join(format!("appmanifest_{}.acf", appid));
+            match appid.parse::<u32>() {
+                Ok(appid) => manifests.push((appid, path)),
+                Err(err) => {
+                    result
+                        .errors
+                        .push(format!("{}: invalid app ID: {}", path.display(), err))
                 }
-            };
-            let appstate: AppState = keyvalues_serde::from_str(contents.as_str())?;
-            let name = appstate.name;
-            result.insert(appid.parse::<u32>()?, name.clone());
+            }
         }
     }
-    Ok(result)
+
+    let read: Vec<(u32, String, Result<Option<CachedManifest>, String>)> = manifests
+        .par_iter()
+        .map(|(appid, path)| {
+            let key = path.to_string_lossy().to_string();
+            let manifest = read_manifest(path, cache.manifests.get(&key));
+            (*appid, key, manifest)
+        })
+        .collect();
+
+    cache.manifests.clear();
+    for (appid, key, manifest) in read.into_iter() {
+        match manifest {
+            Ok(Some(manifest)) => {
+                result.names.insert(appid, manifest.name.clone());
+                result
+                    .installdirs
+                    .insert(appid, manifest.installdir.clone());
+                cache.manifests.insert(key, manifest);
+            }
+            Ok(None) => {}
+            Err(err) => result.errors.push(format!("{}: {}", key, err)),
+        }
+    }
+    result
+}
+
+pub fn read_app_names(apps: &(Vec<PathBuf>, Vec<Vec<String>>)) -> AppNames {
+    // A damaged cache is rebuilt, there is nothing in it worth keeping
+    let mut cache: ManifestCache =
+        confy::load(consts::CODE_NAME, Some(CACHE_NAME)).unwrap_or_default();
+    let result = read_app_names_cached(apps, &mut cache);
+    if let Err(err) = confy::store(consts::CODE_NAME, Some(CACHE_NAME), cache) {
+        warn!("error storing manifest cache: {}", err);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_app_names() {
+        let apps = (
+            vec![PathBuf::from("tests/library")],
+            vec![vec![
+                "1111111".to_string(),
+                "2222222".to_string(),
+                "3333333".to_string(),
+            ]],
+        );
+        let mut cache = ManifestCache::default();
+        let result = read_app_names_cached(&apps, &mut cache);
+        assert_eq!(
+            result.names.get(&1111111),
+            Some(&"The Witcher 3: Wild Hunt".to_string())
+        );
+        assert_eq!(result.names.len(), 1);
+        assert_eq!(
+            result.installdirs.get(&1111111),
+            Some(&"The Witcher 3".to_string())
+        );
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].contains("appmanifest_2222222.acf"));
+        assert_eq!(cache.manifests.len(), 1);
+        let stored: ManifestCache = toml::from_str(&toml::to_string(&cache).unwrap()).unwrap();
+        assert_eq!(stored.manifests, cache.manifests);
+
+        let key = "tests/library/steamapps/appmanifest_1111111.acf";
+        cache.manifests.get_mut(key).unwrap().name = "Cached".to_string();
+        let result = read_app_names_cached(&apps, &mut cache);
+        assert_eq!(result.names.get(&1111111), Some(&"Cached".to_string()));
+
+        cache.manifests.get_mut(key).unwrap().modified = SystemTime::UNIX_EPOCH;
+        let result = read_app_names_cached(&apps, &mut cache);
+        assert_eq!(
+            result.names.get(&1111111),
+            Some(&"The Witcher 3: Wild Hunt".to_string())
+        );
+    }
 }
@@ -0,0 +1,162 @@
+//! Proton prefixes under `steamapps/compatdata/<appid>` in each library.
+
+use crate::fsutil;
+use log::debug;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prefix {
+    pub appid: u32,
+    pub path: PathBuf,
+    /// Bytes of all files, without following links
+    pub size: u64,
+    /// The Proton version that last updated the prefix, from its `version` file
+    pub version: Option<String>,
+    pub last_used: Option<SystemTime>,
+}
+
+pub fn prefix_dir(library: &Path, appid: u32) -> PathBuf {
+    library.join("steamapps/compatdata").join(appid.to_string())
+}
+
+/// A `file://` URL of the path, with the bytes that cannot appear in a URL percent-encoded.
+pub fn file_url(path: &Path) -> String {
+    let mut url = "file://".to_string();
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'.' | b'_' | b'~' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    url
+}
+
+pub fn inspect(path: &Path, appid: u32) -> Prefix {
+    let version = fs::read_to_string(path.join("version"))
+        .ok()
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty());
+    // Wine writes the registry when the game exits
+    let last_used = ["pfx/user.reg", "pfx/system.reg", "version"]
+        .iter()
+        .filter_map(|file| {
+            fs::metadata(path.join(file))
+                .and_then(|m| m.modified())
+                .ok()
+        })
+        .max();
+    Prefix {
+        appid,
+        path: path.to_path_buf(),
+        size: fsutil::dir_size(path),
+        version,
+        last_used,
+    }
+}
+
+/// Prefixes of apps that are not installed in the same library, `installed` holding each app ID
+/// with the library that lists it. Non-Steam shortcuts, whose IDs
+/// have the high bit set, and the shared prefix 0 are never orphaned.
+pub fn orphans(libraries: &[PathBuf], installed: &BTreeSet<(u32, PathBuf)>) -> Vec<Prefix> {
+    let mut result = vec![];
+    for library in libraries.iter() {
+        let Ok(entries) = fs::read_dir(library.join("steamapps/compatdata")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(appid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+                continue;
+            };
+            if appid == 0 || appid >= 1 << 31 || !entry.path().is_dir() {
+                continue;
+            }
+            // A prefix left in another library after the game moved is orphaned as well
+            if !installed.contains(&(appid, library.clone())) {
+                result.push(inspect(&entry.path(), appid));
+            }
+        }
+    }
+    result.sort_by_key(|prefix| prefix.appid);
+    debug!("orphaned prefixes: {}", result.len());
+    result
+}
+
+/// Writes the prefix to a gzip compressed tarball with the system's `tar`.
+pub fn backup(path: &Path, tarball: &Path) -> Result<(), Box<dyn Error>> {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(format!("invalid prefix: {}", path.display()).into());
+    };
+    debug!("backing up {} to {}", path.display(), tarball.display());
+    let output = Command::new("tar")
+        .arg("-czf")
+        .arg(tarball)
+        .arg("-C")
+        .arg(parent)
+        .arg(name)
+        .output()?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr)
+            .trim()
+            .to_string()
+            .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect() {
+        let library = PathBuf::from("tests/library");
+        let prefix = inspect(&prefix_dir(&library, 1111111), 1111111);
+        assert_eq!(prefix.version, Some("9.0-203".to_string()));
+        assert_eq!(prefix.size, 8 + 24);
+        assert!(prefix.last_used.is_some());
+
+        let installed = BTreeSet::from([(1111111, library.clone())]);
+        let orphans = orphans(&[library], &installed);
+        assert_eq!(
+            orphans
+                .iter()
+                .map(|prefix| prefix.appid)
+                .collect::<Vec<u32>>(),
+            vec![4444444]
+        );
+        assert_eq!(orphans[0].version, Some("GE-Proton8-25".to_string()));
+    }
+
+    #[test]
+    fn test_file_url() {
+        assert_eq!(
+            file_url(Path::new("/games/compatdata/1111111")),
+            "file:///games/compatdata/1111111"
+        );
+        assert_eq!(
+            file_url(Path::new("/mnt/My Games #2/100%/Pokémon")),
+            "file:///mnt/My%20Games%20%232/100%25/Pok%C3%A9mon"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_backup() {
+        let tarball =
+            std::env::temp_dir().join(format!("compatdata-{}.tar.gz", std::process::id()));
+        backup(
+            Path::new("tests/library/steamapps/compatdata/1111111"),
+            &tarball,
+        )
+        .unwrap();
+        assert!(fs::metadata(&tarball).unwrap().len() > 0);
+        fs::remove_file(&tarball).unwrap();
+    }
+}
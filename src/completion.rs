@@ -0,0 +1,237 @@
+//! Suggestions for the word at the cursor in launch options.
+
+use crate::env_catalog;
+use crate::launch_options::{self, COMMAND};
+use log::debug;
+use std::collections::BTreeMap;
+use std::env;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// Commands that are commonly put before `%command%`.
+const WRAPPERS: [&str; 12] = [
+    "gamemoderun",
+    "gamescope",
+    "mangohud",
+    "prime-run",
+    "obs-gamecapture",
+    "pw-jack",
+    "firejail",
+    "taskset",
+    "nice",
+    "strace",
+    "env",
+    "steam-runtime-launch-client",
+];
+
+/// Suggestions that replace the word before the cursor.
+#[derive(Debug, PartialEq)]
+pub struct Completions {
+    /// Characters replaced by a completion
+    pub range: Range<usize>,
+    /// The replacement and its description
+    pub items: Vec<(String, &'static str)>,
+}
+
+/// Words offered besides variables and wrappers, collected once when editing starts.
+#[derive(Debug, Default)]
+pub struct Words {
+    /// From the launch options of the other apps
+    pub used: Vec<String>,
+    /// From the launch options saved in the other profiles
+    pub presets: Vec<String>,
+}
+
+/// The known wrappers that are installed, looked up on `PATH` once.
+pub fn installed_wrappers() -> &'static [String] {
+    static INSTALLED: OnceLock<Vec<String>> = OnceLock::new();
+    INSTALLED.get_or_init(|| {
+        let dirs: Vec<_> = env::var_os("PATH")
+            .map(|path| env::split_paths(&path).collect())
+            .unwrap_or_default();
+        let installed: Vec<String> = WRAPPERS
+            .iter()
+            .filter(|wrapper| dirs.iter().any(|dir| dir.join(wrapper).is_file()))
+            .map(|wrapper| wrapper.to_string())
+            .collect();
+        debug!("installed wrappers: {:?}", installed);
+        installed
+    })
+}
+
+/// The words of other launch options, most used first.
+pub fn used_words<'a>(all_launch_options: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for launch_options in all_launch_options {
+        for word in launch_options::split(launch_options) {
+            if word != COMMAND {
+                *counts.entry(word).or_default() += 1;
+            }
+        }
+    }
+    let mut words: Vec<(String, usize)> = counts.into_iter().collect();
+    words.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    words.into_iter().map(|(word, _)| word).collect()
+}
+
+/// Suggests `%command%`, variables, wrappers and words used elsewhere for the word that ends at
+/// the cursor. Variables are only suggested before any command, wrappers before `%command%`.
+pub fn complete(
+    text: &str,
+    cursor: usize,
+    wrappers: &[String],
+    words: &Words,
+) -> Option<Completions> {
+    let chars: Vec<char> = text.chars().collect();
+    if cursor > chars.len() || chars.get(cursor).is_some_and(|c| !c.is_whitespace()) {
+        return None;
+    }
+    let start = chars[..cursor]
+        .iter()
+        .rposition(|c| c.is_whitespace())
+        .map_or(0, |i| i + 1);
+    let word: String = chars[start..cursor].iter().collect();
+    if word.is_empty() {
+        return None;
+    }
+    let is_env = |word: &String| {
+        word.split_once('=')
+            .is_some_and(|(name, _)| launch_options::is_env_name(name))
+    };
+    let before = launch_options::split(&chars[..start].iter().collect::<String>());
+    let only_env = before.iter().all(is_env);
+    let after_command = before.iter().any(|word| word == COMMAND);
+
+    let mut items: Vec<(String, &'static str)> = vec![];
+    if COMMAND.starts_with(&word) && word != COMMAND && !text.contains(COMMAND) {
+        items.push((COMMAND.to_string(), "The game command"));
+    }
+    if only_env {
+        items.extend(env_catalog::complete(&word));
+    }
+    if word.chars().count() >= 2 {
+        if !after_command {
+            items.extend(
+                wrappers
+                    .iter()
+                    .filter(|wrapper| wrapper.starts_with(&word) && **wrapper != word)
+                    .map(|wrapper| (wrapper.clone(), "Installed wrapper")),
+            );
+        }
+        for (words, description) in [
+            (&words.used, "Used by other apps"),
+            (&words.presets, "Saved in another profile"),
+        ] {
+            items.extend(
+                words
+                    .iter()
+                    .filter(|used| used.starts_with(&word) && **used != word)
+                    .filter(|used| (only_env || !is_env(used)) && !before.contains(used))
+                    .map(|used| (used.clone(), description)),
+            );
+        }
+    }
+    let mut seen = vec![];
+    items.retain(|(replacement, _)| {
+        let new = !seen.contains(replacement);
+        seen.push(replacement.clone());
+        new
+    });
+    (!items.is_empty()).then_some(Completions {
+        range: start..cursor,
+        items,
+    })
+}
+
+/// Replaces the completed characters, returning the text and the cursor after the replacement.
+pub fn insert(text: &str, range: &Range<usize>, replacement: &str) -> (String, usize) {
+    let mut chars: Vec<char> = text.chars().collect();
+    chars.splice(range.clone(), replacement.chars());
+    (
+        chars.into_iter().collect(),
+        range.start + replacement.chars().count(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replacements(completions: Option<Completions>) -> Vec<String> {
+        completions
+            .map(|completions| {
+                completions
+                    .items
+                    .into_iter()
+                    .map(|(replacement, _)| replacement)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_complete() {
+        let wrappers = vec![
+            "gamemoderun".to_string(),
+            "gamescope".to_string(),
+            "obs-gamecapture".to_string(),
+        ];
+        let used = Words {
+            used: used_words(
+                [
+                    "PROTON_LOG=1 gamemoderun %command% -novid".to_string(),
+                    "gamescope -f -- %command% -nosplash".to_string(),
+                    "PROTON_LOG=1 %command% -novid".to_string(),
+                ]
+                .iter(),
+            ),
+            presets: vec!["-novid".to_string(), "-nojoy".to_string()],
+        };
+        assert_eq!(used.used[0], "-novid");
+        assert_eq!(used.used[1], "PROTON_LOG=1");
+
+        let completions = complete("PROTON_LOG=1 DXVK_LO", 20, &wrappers, &used).unwrap();
+        assert_eq!(completions.range, 13..20);
+        assert_eq!(
+            replacements(Some(completions)),
+            vec!["DXVK_LOG_LEVEL=", "DXVK_LOG_PATH="]
+        );
+        assert_eq!(
+            replacements(complete("PROTON_LOG=1 ga", 15, &wrappers, &used)),
+            vec!["gamemoderun", "gamescope"]
+        );
+        assert_eq!(
+            replacements(complete("gamemoderun %c", 14, &wrappers, &used)),
+            vec!["%command%"]
+        );
+        assert_eq!(
+            replacements(complete("%command% -n", 12, &wrappers, &used)),
+            vec!["-novid", "-nosplash", "-nojoy"]
+        );
+        assert_eq!(
+            complete("%command% -noj", 14, &wrappers, &used)
+                .unwrap()
+                .items,
+            vec![("-nojoy".to_string(), "Saved in another profile")]
+        );
+        // Variables only go before commands, wrappers only before %command%
+        assert_eq!(
+            replacements(complete("gamemoderun PROTON_", 19, &wrappers, &used)),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            replacements(complete("%command% ob", 12, &wrappers, &used)),
+            Vec::<String>::new()
+        );
+        assert_eq!(complete("PROTON_LOG", 5, &wrappers, &used), None);
+        assert_eq!(complete("-novid ", 7, &wrappers, &used), None);
+    }
+
+    #[test]
+    fn test_insert() {
+        assert_eq!(
+            insert("VKD3D_DEBUG=w %command%", &(0..13), "VKD3D_DEBUG=warn"),
+            ("VKD3D_DEBUG=warn %command%".to_string(), 16)
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::AppSort;
+use crate::schema::{self, Migration};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const MIGRATIONS: &[Migration] = &[v0_to_v1];
+pub const VERSION: u32 = MIGRATIONS.len() as u32;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub version: u32,
+    pub steam_config: Option<String>,
+    pub default_launch_options: Option<String>,
+    pub app_sort: Option<AppSort>,
+    pub protondb: Option<bool>,
+    pub git_history: Option<bool>,
+    pub team_dir: Option<String>,
+    pub profile: Option<String>,
+    pub saved_filters: Option<BTreeMap<String, String>>,
+}
+
+/// Version 0 stored the sort order by its label, version 1 by its name.
+fn v0_to_v1(table: &mut toml::Table) -> Result<(), Box<dyn Error>> {
+    if let Some(toml::Value::String(label)) = table.get("app_sort") {
+        let app_sort = AppSort::from_str(label).unwrap_or_default();
+        table.insert("app_sort".to_string(), toml::Value::try_from(app_sort)?);
+    }
+    Ok(())
+}
+
+/// A directory next to the config file for the files written for other programs to read, such as
+/// MangoHud configs or launch scripts.
+pub fn managed_dir(name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let path = confy::get_configuration_file_path(crate::consts::CODE_NAME, name)?;
+    Ok(path.with_file_name(name))
+}
+
+/// The `<appid>.<extension>` file of an app in a managed directory.
+pub fn managed_file(dir: &str, appid: u32, extension: &str) -> Result<PathBuf, Box<dyn Error>> {
+    Ok(managed_dir(dir)?.join(format!("{}.{}", appid, extension)))
+}
+
+pub fn load() -> Config {
+    schema::load(None, MIGRATIONS)
+}
+
+pub fn store(mut config: Config) {
+    config.version = VERSION;
+    if let Err(err) = schema::store(None, config) {
+        warn!("error storing config: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v0_to_v1() {
+        let mut table: toml::Table = "app_sort = \"⬇ App Name\"\nprotondb = true"
+            .parse()
+            .unwrap();
+        schema::migrate(&mut table, MIGRATIONS).unwrap();
+        let config: Config = table.try_into().unwrap();
+        assert_eq!(config.version, 1);
+        assert!(config.app_sort == Some(AppSort::NameDescending));
+        assert_eq!(config.protondb, Some(true));
+    }
+}
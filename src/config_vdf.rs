@@ -0,0 +1,69 @@
+use log::warn;
+use serde::Deserialize;
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+struct InstallConfigStore {
+    software: Software,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+struct Software {
+    #[serde(alias = "valve")]
+    valve: Valve,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+struct Valve {
+    #[serde(alias = "steam")]
+    steam: Steam,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Steam {
+    #[serde(rename = "CompatToolMapping", default)]
+    compat_tool_mapping: BTreeMap<String, Value>,
+}
+
+/// Reads the compatibility tool chosen per app, app ID 0 being the default for all apps.
+pub fn read_compat_tools(filename: PathBuf) -> Result<BTreeMap<u32, String>, Box<dyn Error>> {
+    let mut result = BTreeMap::new();
+    let contents = fs::read_to_string(filename)?;
+    let config: InstallConfigStore = keyvalues_serde::from_str(contents.as_str())?;
+    for (appid, values) in config.software.valve.steam.compat_tool_mapping.into_iter() {
+        let Ok(appid) = appid.parse::<u32>() else {
+            warn!("read_compat_tools appid error: {}", appid);
+            continue;
+        };
+        let values = values.deserialize_into::<BTreeMap<String, String>>()?;
+        if let Some(name) = values.get("name")
+            && !name.is_empty()
+        {
+            result.insert(appid, name.clone());
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_compat_tools() {
+        let compat_tools = read_compat_tools(PathBuf::from("tests/config.vdf")).unwrap();
+        assert_eq!(compat_tools.get(&0), Some(&"proton_9".to_string()));
+        assert_eq!(
+            compat_tools.get(&1111111),
+            Some(&"GE-Proton9-20".to_string())
+        );
+        assert_eq!(compat_tools.get(&9999999), None);
+    }
+}
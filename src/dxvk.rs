@@ -0,0 +1,218 @@
+//! Per-game `dxvk.conf` files, passed to DXVK with `DXVK_CONFIG_FILE`, and the VKD3D-Proton
+//! options of `VKD3D_CONFIG`, which has no config file.
+
+use crate::config;
+use crate::launch_options;
+use log::debug;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE: &str = "DXVK_CONFIG_FILE";
+pub const VKD3D_CONFIG: &str = "VKD3D_CONFIG";
+
+/// The `key = value` lines of a config file.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DxvkConfig {
+    pub entries: Vec<(String, String)>,
+}
+
+pub fn parse(text: &str) -> DxvkConfig {
+    let entries = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+    DxvkConfig { entries }
+}
+
+impl DxvkConfig {
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Keys that are already set keep their line, so edited files stay close to the original.
+    pub fn set(&mut self, key: &str, value: String) {
+        match self.entries.iter_mut().find(|(name, _)| name == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key.to_string(), value)),
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.entries.retain(|(name, _)| name != key);
+    }
+}
+
+impl std::fmt::Display for DxvkConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (key, value) in self.entries.iter() {
+            writeln!(f, "{} = {}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// The `dxvk.conf` written for the app.
+pub fn managed_path(appid: u32) -> Result<PathBuf, Box<dyn Error>> {
+    config::managed_file("dxvk", appid, "conf")
+}
+
+/// Existing config files DXVK would read for the app: the one in its launch options, then a
+/// `dxvk.conf` shipped in the install directory or next to one of its executables.
+pub fn detect(install_dir: &Path, launch_options: &str, executables: &[String]) -> Vec<PathBuf> {
+    let mut candidates = vec![];
+    if let Some(path) = launch_options::parse(launch_options).env(CONFIG_FILE) {
+        candidates.push(PathBuf::from(path));
+    }
+    candidates.push(install_dir.join("dxvk.conf"));
+    for executable in executables.iter() {
+        // appinfo.vdf uses Windows separators for Windows games
+        let executable = executable.replace('\\', "/");
+        if let Some(dir) = Path::new(&executable).parent() {
+            candidates.push(install_dir.join(dir).join("dxvk.conf"));
+        }
+    }
+    let mut result: Vec<PathBuf> = vec![];
+    for path in candidates.into_iter() {
+        if path.is_file() && !result.contains(&path) {
+            result.push(path);
+        }
+    }
+    debug!("dxvk configs: {:?}", result);
+    result
+}
+
+pub fn read(path: &Path) -> Result<DxvkConfig, Box<dyn Error>> {
+    Ok(parse(&fs::read_to_string(path)?))
+}
+
+/// Writes the config file, or removes it when nothing is set so that it is not read again.
+/// Returns whether the file was written.
+pub fn write(path: &Path, config: &DxvkConfig) -> Result<bool, Box<dyn Error>> {
+    if config.entries.is_empty() {
+        if path.is_file() {
+            debug!("removing empty dxvk config: {}", path.display());
+            fs::remove_file(path)?;
+        }
+        return Ok(false);
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, config.to_string())?;
+    Ok(true)
+}
+
+/// The options in `VKD3D_CONFIG` of the launch options.
+pub fn vkd3d_options(text: &str) -> Vec<String> {
+    launch_options::parse(text)
+        .env(VKD3D_CONFIG)
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|option| !option.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Sets a variable where it is, or adds it, or removes it when `value` is `None`.
+fn set_env(launch_options: &mut launch_options::LaunchOptions, name: &str, value: Option<String>) {
+    match value {
+        Some(value) => match launch_options.env.iter_mut().find(|(key, _)| key == name) {
+            Some(entry) => entry.1 = value,
+            None => launch_options.env.push((name.to_string(), value)),
+        },
+        None => launch_options.env.retain(|(key, _)| key != name),
+    }
+}
+
+/// Points the launch options at the config file, or removes the variable when `path` is `None`,
+/// and replaces the VKD3D-Proton options. The other words are left as they are.
+pub fn apply(text: &str, path: Option<&Path>, vkd3d: &[String]) -> String {
+    let mut launch_options = launch_options::parse(text);
+    set_env(
+        &mut launch_options,
+        CONFIG_FILE,
+        path.map(|path| path.to_string_lossy().to_string()),
+    );
+    set_env(
+        &mut launch_options,
+        VKD3D_CONFIG,
+        (!vkd3d.is_empty()).then(|| vkd3d.join(",")),
+    );
+    if !launch_options.env.is_empty() {
+        launch_options.has_command = true;
+    }
+    launch_options.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let install_dir = Path::new("tests/library/steamapps/common/The Witcher 3");
+        let detected = detect(install_dir, "", &["bin\\x64\\witcher3.exe".to_string()]);
+        assert_eq!(detected, vec![install_dir.join("bin/x64/dxvk.conf")]);
+        let mut config = read(&detected[0]).unwrap();
+        assert_eq!(config.value("dxgi.maxFrameRate"), Some("60"));
+        assert_eq!(config.value("dxvk.hud"), Some("fps,memory"));
+
+        config.set("dxgi.maxFrameRate", "144".to_string());
+        config.remove("dxvk.hud");
+        config.set("d3d11.samplerAnisotropy", "16".to_string());
+        assert_eq!(
+            config.to_string(),
+            "dxgi.maxFrameRate = 144\ndxgi.hideNvidiaGpu = False\nd3d11.samplerAnisotropy = 16\n"
+        );
+        assert_eq!(parse(&config.to_string()), config);
+    }
+
+    #[test]
+    fn test_apply() {
+        let path = Path::new("/home/user/.config/steam-optionx/dxvk/1111111.conf");
+        let text = "VKD3D_CONFIG=dxr,force_static_cbv mangohud %command%";
+        assert_eq!(vkd3d_options(text), vec!["dxr", "force_static_cbv"]);
+        assert_eq!(
+            apply(text, Some(path), &["dxr11".to_string()]),
+            "VKD3D_CONFIG=dxr11 \
+             DXVK_CONFIG_FILE=/home/user/.config/steam-optionx/dxvk/1111111.conf mangohud %command%"
+        );
+        assert_eq!(apply(text, None, &[]), "mangohud %command%");
+        assert_eq!(
+            apply("-novid", Some(path), &[]),
+            "DXVK_CONFIG_FILE=/home/user/.config/steam-optionx/dxvk/1111111.conf %command% -novid"
+        );
+        let text = "DXVK_CONFIG_FILE=$HOME/dxvk.conf FOO=~/x VKD3D_CONFIG=dxr %command%; echo $?";
+        assert_eq!(
+            apply(text, Some(path), &["dxr".to_string()]),
+            "DXVK_CONFIG_FILE=/home/user/.config/steam-optionx/dxvk/1111111.conf FOO=~/x \
+             VKD3D_CONFIG=dxr %command%; echo $?"
+        );
+        assert_eq!(apply(text, None, &[]), "FOO=~/x %command%; echo $?");
+    }
+
+    #[test]
+    fn test_write() {
+        let path = std::env::temp_dir()
+            .join(format!("dxvk-{}", std::process::id()))
+            .join("1111111.conf");
+        let mut config = parse("dxgi.maxFrameRate = 60\n");
+        assert!(write(&path, &config).unwrap());
+        assert_eq!(read(&path).unwrap(), config);
+        config.remove("dxgi.maxFrameRate");
+        assert!(!write(&path, &config).unwrap());
+        assert!(!path.exists());
+        fs::remove_dir(path.parent().unwrap()).unwrap();
+    }
+}
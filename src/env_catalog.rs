@@ -0,0 +1,221 @@
+//! Known environment variables of Proton, DXVK, VKD3D-Proton and Wine, from
+//! `assets/env_catalog.toml`.
+
+use crate::launch_options;
+use log::debug;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+/// Variables with these prefixes are reported when they are not in the catalog.
+const CHECKED_PREFIXES: [&str; 4] = ["PROTON_", "DXVK_", "VKD3D_", "WINE_"];
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Catalog {
+    pub version: u32,
+    pub variables: Vec<Variable>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Variable {
+    pub name: String,
+    pub description: String,
+    /// Every accepted value, empty for free-form values
+    #[serde(default)]
+    pub values: Vec<String>,
+    /// Suggested free-form values
+    #[serde(default)]
+    pub examples: Vec<String>,
+    /// First Proton version that honors the variable
+    pub since: Option<String>,
+    /// First Proton version that ignores it again
+    pub removed: Option<String>,
+    pub note: Option<String>,
+}
+
+pub fn catalog() -> &'static Catalog {
+    static CATALOG: OnceLock<Catalog> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        let catalog: Catalog = toml::from_str(include_str!("../assets/env_catalog.toml"))
+            .expect("Failed to parse the environment variable catalog");
+        debug!(
+            "environment variable catalog v{}: {} variables",
+            catalog.version,
+            catalog.variables.len()
+        );
+        catalog
+    })
+}
+
+pub fn get(name: &str) -> Option<&'static Variable> {
+    catalog()
+        .variables
+        .iter()
+        .find(|variable| variable.name == name)
+}
+
+impl Variable {
+    pub fn versions(&self) -> Option<String> {
+        match (&self.since, &self.removed) {
+            (Some(since), Some(removed)) => Some(format!("Proton {} until {}", since, removed)),
+            (Some(since), None) => Some(format!("Proton {} and later", since)),
+            (None, Some(removed)) => Some(format!("Proton before {}", removed)),
+            (None, None) => None,
+        }
+    }
+
+    /// Whether the variable is a flag, turned on with 1.
+    pub fn is_flag(&self) -> bool {
+        self.values == ["1"]
+    }
+
+    /// Whether the value is accepted. Flags are also left off with 0 or an empty value.
+    pub fn accepts(&self, value: &str) -> bool {
+        self.values.is_empty()
+            || self.values.iter().any(|accepted| accepted == value)
+            || (self.is_flag() && (value == "0" || value.is_empty()))
+    }
+
+    /// The description with the accepted values and versions, for tooltips.
+    pub fn help(&self) -> String {
+        let mut lines = vec![format!("{}: {}", self.name, self.description)];
+        if !self.values.is_empty() {
+            lines.push(format!("Values: {}", self.values.join(", ")));
+        } else if !self.examples.is_empty() {
+            lines.push(format!("Examples: {}", self.examples.join(", ")));
+        }
+        lines.extend(self.versions());
+        lines.extend(self.note.iter().map(|note| format!("⚠ {}", note)));
+        lines.join("\n")
+    }
+}
+
+/// Describes unknown, removed or deprecated variables and values that are not accepted.
+pub fn lint(text: &str) -> Vec<String> {
+    let mut problems = vec![];
+    for (name, value) in launch_options::parse(text).env.iter() {
+        let Some(variable) = get(name) else {
+            if CHECKED_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+            {
+                problems.push(format!("Unknown variable: {}", name));
+            }
+            continue;
+        };
+        match (&variable.removed, &variable.note) {
+            (Some(removed), Some(note)) => problems.push(format!(
+                "{} was removed in Proton {}: {}",
+                name, removed, note
+            )),
+            (Some(removed), None) => {
+                problems.push(format!("{} was removed in Proton {}", name, removed))
+            }
+            (None, Some(note)) => problems.push(format!("{}: {}", name, note)),
+            (None, None) => {}
+        }
+        if variable.is_flag() && !variable.accepts(value) {
+            problems.push(format!("{}: expected 1, or 0 to leave it off", name));
+        } else if !variable.accepts(value) {
+            problems.push(format!(
+                "{}: expected one of {}",
+                name,
+                variable.values.join(", ")
+            ));
+        }
+    }
+    problems
+}
+
+/// Variable names starting with the word, or the values of a variable after `=`, each with
+/// its description.
+pub fn complete(word: &str) -> Vec<(String, &'static str)> {
+    match word.split_once('=') {
+        Some((name, value)) => {
+            let Some(variable) = get(name) else {
+                return vec![];
+            };
+            variable
+                .values
+                .iter()
+                .chain(variable.examples.iter())
+                .filter(|candidate| candidate.starts_with(value) && *candidate != value)
+                .map(|candidate| {
+                    (
+                        format!("{}={}", name, candidate),
+                        variable.description.as_str(),
+                    )
+                })
+                .collect()
+        }
+        // Lowercase words are commands such as mangohud
+        None if word.len() >= 2
+            && word
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_') =>
+        {
+            catalog()
+                .variables
+                .iter()
+                .filter(|variable| variable.name.starts_with(word))
+                .map(|variable| (format!("{}=", variable.name), variable.description.as_str()))
+                .collect()
+        }
+        None => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog() {
+        assert_eq!(catalog().version, 1);
+        let mut names: Vec<&str> = catalog()
+            .variables
+            .iter()
+            .map(|variable| variable.name.as_str())
+            .collect();
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count, "duplicate variables");
+        assert!(
+            get("PROTON_LOG")
+                .unwrap()
+                .help()
+                .contains("Proton 3.7 and later")
+        );
+    }
+
+    #[test]
+    fn test_lint() {
+        assert!(lint("PROTON_LOG=1 DXVK_HUD=fps MY_VAR=1 %command%").is_empty());
+        assert!(lint("PROTON_LOG=+timestamp,+seh %command%").is_empty());
+        assert!(lint("PROTON_USE_WINED3D=0 PROTON_NO_ESYNC= %command%").is_empty());
+        assert_eq!(
+            lint("PROTON_USE_WINED3D=yes %command%"),
+            vec!["PROTON_USE_WINED3D: expected 1, or 0 to leave it off"]
+        );
+        assert_eq!(
+            lint("PROTON_LGO=1 PROTON_USE_D9VK=1 DXVK_LOG_LEVEL=loud %command%"),
+            vec![
+                "Unknown variable: PROTON_LGO",
+                "PROTON_USE_D9VK was removed in Proton 5.0: DXVK handles Direct3D 9 by default",
+                "DXVK_LOG_LEVEL: expected one of none, error, warn, info, debug",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_complete() {
+        let names: Vec<String> = complete("DXVK_LO")
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, vec!["DXVK_LOG_LEVEL=", "DXVK_LOG_PATH="]);
+        assert_eq!(complete("VKD3D_DEBUG=w")[0].0, "VKD3D_DEBUG=warn");
+        assert!(complete("mangohud").is_empty());
+        assert!(complete("PROTON_LOG=1").is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+//! Fuzzy matching of app names that ignores case, punctuation, symbols such as `®` and `™`
+//! and diacritics, so `witcher3` finds "The Witcher® 3: Wild Hunt".
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+#[derive(Debug, PartialEq)]
+pub struct Match {
+    pub score: i64,
+    /// Indices of the matched characters in the original text
+    pub positions: Vec<usize>,
+}
+
+#[derive(Default)]
+struct Normalized {
+    chars: Vec<char>,
+    /// Index of the original character each normalized character came from
+    positions: Vec<usize>,
+    /// Whether a character starts a word
+    boundaries: Vec<bool>,
+}
+
+fn normalize(text: &str) -> Normalized {
+    let mut result = Normalized::default();
+    let mut boundary = true;
+    for (i, c) in text.chars().enumerate() {
+        let mut kept = false;
+        for c in std::iter::once(c)
+            .nfd()
+            .filter(|c| !is_combining_mark(*c) && c.is_alphanumeric())
+        {
+            for c in c.to_lowercase() {
+                result.chars.push(c);
+                result.positions.push(i);
+                result.boundaries.push(boundary);
+                boundary = false;
+            }
+            kept = true;
+        }
+        if !kept {
+            boundary = true;
+        }
+    }
+    result
+}
+
+pub fn find(pattern: &str, text: &str) -> Option<Match> {
+    let pattern = normalize(pattern).chars;
+    if pattern.is_empty() {
+        return Some(Match {
+            score: 0,
+            positions: vec![],
+        });
+    }
+    let text = normalize(text);
+
+    // Find the first complete match, then walk back from its end for a tighter one
+    let mut p = 0;
+    let end = text.chars.iter().position(|c| {
+        if *c == pattern[p] {
+            p += 1;
+        }
+        p == pattern.len()
+    })?;
+    let mut p = pattern.len();
+    let mut matched = vec![];
+    for i in (0..=end).rev() {
+        if p > 0 && text.chars[i] == pattern[p - 1] {
+            p -= 1;
+            matched.push(i);
+        }
+    }
+    matched.reverse();
+    let start = matched[0];
+
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    let mut positions = vec![];
+    for i in matched {
+        score += 16;
+        if text.boundaries[i] {
+            score += 8;
+        }
+        match previous {
+            Some(previous) if previous + 1 == i => score += 8,
+            Some(previous) => score -= (i - previous - 1).min(8) as i64,
+            None => {}
+        }
+        previous = Some(i);
+        if positions.last() != Some(&text.positions[i]) {
+            positions.push(text.positions[i]);
+        }
+    }
+    score -= (start as i64).min(16) / 4;
+    Some(Match { score, positions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        let witcher = "The Witcher® 3: Wild Hunt";
+        assert!(find("witcher3", witcher).is_some());
+        assert!(find("witcher 3", witcher).is_some());
+        assert!(find("The Witcher™", witcher).is_some());
+        assert!(find("witcher4", witcher).is_none());
+        assert!(find("pokemon", "Pokémon Legends").is_some());
+        assert_eq!(
+            find("hl2", "Half-Life 2").unwrap().positions,
+            vec![0, 5, 10]
+        );
+        assert_eq!(
+            find("3wild", witcher).unwrap().positions,
+            vec![13, 16, 17, 18, 19]
+        );
+    }
+
+    #[test]
+    fn test_score() {
+        let score = |pattern: &str, text: &str| find(pattern, text).unwrap().score;
+        assert!(score("portal", "Portal 2") > score("portal", "Pirates of the Caribbean: Portal"));
+        assert!(score("dark", "Dark Souls") > score("dark", "Dead Rising 4: Frank's Big Package"));
+    }
+}
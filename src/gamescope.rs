@@ -0,0 +1,455 @@
+//! The `gamescope … --` wrapper in launch options, e.g. `gamescope -W 2560 -H 1440 -- %command%`.
+
+use crate::launch_options;
+use std::ops::Range;
+use std::str::FromStr;
+
+pub const COMMAND: &str = "gamescope";
+
+/// Flags followed by a separate value, which is not where the next command starts.
+const VALUE_FLAGS: [&str; 17] = [
+    "-W",
+    "--output-width",
+    "-H",
+    "--output-height",
+    "-w",
+    "--nested-width",
+    "-h",
+    "--nested-height",
+    "-r",
+    "--nested-refresh",
+    "--framerate-limit",
+    "-F",
+    "--filter",
+    "-S",
+    "--scaler",
+    "--sharpness",
+    "--fsr-sharpness",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    Linear,
+    Nearest,
+    Fsr,
+    Nis,
+    Pixel,
+}
+
+impl Filter {
+    pub const ALL: [Filter; 5] = [
+        Filter::Linear,
+        Filter::Nearest,
+        Filter::Fsr,
+        Filter::Nis,
+        Filter::Pixel,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Filter::Linear => "linear",
+            Filter::Nearest => "nearest",
+            Filter::Fsr => "fsr",
+            Filter::Nis => "nis",
+            Filter::Pixel => "pixel",
+        }
+    }
+}
+
+impl std::fmt::Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Filter::ALL
+            .into_iter()
+            .find(|filter| filter.name() == s)
+            .ok_or(format!("unknown filter: {}", s))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scaler {
+    Auto,
+    Integer,
+    Fit,
+    Fill,
+    Stretch,
+}
+
+impl Scaler {
+    pub const ALL: [Scaler; 5] = [
+        Scaler::Auto,
+        Scaler::Integer,
+        Scaler::Fit,
+        Scaler::Fill,
+        Scaler::Stretch,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Scaler::Auto => "auto",
+            Scaler::Integer => "integer",
+            Scaler::Fit => "fit",
+            Scaler::Fill => "fill",
+            Scaler::Stretch => "stretch",
+        }
+    }
+}
+
+impl std::fmt::Display for Scaler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Scaler {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scaler::ALL
+            .into_iter()
+            .find(|scaler| scaler.name() == s)
+            .ok_or(format!("unknown scaler: {}", s))
+    }
+}
+
+/// Gamescope flags, unset values are left to gamescope's defaults.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Gamescope {
+    pub output_width: Option<u32>,
+    pub output_height: Option<u32>,
+    /// The resolution the game renders at
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub refresh_rate: Option<u32>,
+    pub framerate_limit: Option<u32>,
+    pub filter: Option<Filter>,
+    pub scaler: Option<Scaler>,
+    /// 0 is the sharpest, 20 the softest
+    pub sharpness: Option<u32>,
+    pub fullscreen: bool,
+    pub borderless: bool,
+    pub hdr: bool,
+    pub mangoapp: bool,
+    /// Flags this editor does not know, kept as they are
+    pub extra: Vec<String>,
+}
+
+fn is_gamescope(word: &str) -> bool {
+    word == COMMAND || word.ends_with("/gamescope")
+}
+
+/// The gamescope command in the wrappers, up to and including its `--` separator. Without the
+/// separator it ends before the first word that is not a flag, where the next wrapper starts.
+fn segment(wrappers: &[String]) -> Option<Range<usize>> {
+    let start = wrappers.iter().position(|word| is_gamescope(word))?;
+    let mut end = start + 1;
+    while let Some(word) = wrappers.get(end) {
+        if word == "--" {
+            return Some(start..end + 1);
+        }
+        if !word.starts_with('-') {
+            break;
+        }
+        end += if VALUE_FLAGS.contains(&word.as_str()) {
+            2
+        } else {
+            1
+        };
+    }
+    Some(start..end.min(wrappers.len()))
+}
+
+/// Parses the arguments between `gamescope` and `--`.
+pub fn parse(args: &[String]) -> Result<Gamescope, String> {
+    let mut result = Gamescope::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || match &inline {
+            Some(value) => Ok(value.clone()),
+            None => args
+                .next()
+                .cloned()
+                .ok_or(format!("{} needs a value", flag)),
+        };
+        let number = |value: String| {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("{}: invalid number: {}", flag, value))
+        };
+        match flag {
+            "-W" | "--output-width" => result.output_width = Some(number(value()?)?),
+            "-H" | "--output-height" => result.output_height = Some(number(value()?)?),
+            "-w" | "--nested-width" => result.width = Some(number(value()?)?),
+            "-h" | "--nested-height" => result.height = Some(number(value()?)?),
+            "-r" | "--nested-refresh" => result.refresh_rate = Some(number(value()?)?),
+            "--framerate-limit" => result.framerate_limit = Some(number(value()?)?),
+            "-F" | "--filter" => result.filter = Some(value()?.parse()?),
+            "-S" | "--scaler" => result.scaler = Some(value()?.parse()?),
+            "--sharpness" | "--fsr-sharpness" => result.sharpness = Some(number(value()?)?),
+            // Flags from gamescope versions before --filter and --scaler
+            "-U" | "--fsr-upscaling" => result.filter = Some(Filter::Fsr),
+            "-Y" | "--nis-upscaling" => result.filter = Some(Filter::Nis),
+            "-i" | "--integer-scale" => result.scaler = Some(Scaler::Integer),
+            "-f" | "--fullscreen" => result.fullscreen = true,
+            "-b" | "--borderless" => result.borderless = true,
+            "--hdr-enabled" => result.hdr = true,
+            "--mangoapp" => result.mangoapp = true,
+            _ => result.extra.push(arg.clone()),
+        }
+    }
+    Ok(result)
+}
+
+/// Returns the gamescope options of the launch options, or `None` without gamescope.
+pub fn from_launch_options(text: &str) -> Result<Option<Gamescope>, String> {
+    let launch_options = launch_options::parse(text);
+    let Some(range) = segment(&launch_options.wrappers) else {
+        return Ok(None);
+    };
+    let args = &launch_options.wrappers[range.start + 1..range.end];
+    let args = args.strip_suffix(&["--".to_string()]).unwrap_or(args);
+    parse(args).map(Some)
+}
+
+/// Replaces the gamescope command in the launch options, or removes it when `gamescope` is
+/// `None`. A new command goes before the other wrappers.
+pub fn apply(text: &str, gamescope: Option<&Gamescope>) -> String {
+    let mut launch_options = launch_options::parse(text);
+    let (index, command) = match segment(&launch_options.wrappers) {
+        Some(range) => {
+            let command = launch_options.wrappers[range.start].clone();
+            launch_options.wrappers.drain(range.clone());
+            (range.start, command)
+        }
+        None => (0, COMMAND.to_string()),
+    };
+    if let Some(gamescope) = gamescope {
+        let mut words = vec![command];
+        words.extend(gamescope.args());
+        words.push("--".to_string());
+        launch_options.wrappers.splice(index..index, words);
+        launch_options.has_command = true;
+    }
+    launch_options.to_string()
+}
+
+impl Gamescope {
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec![];
+        let mut push = |flag: &str, value: Option<String>| {
+            if let Some(value) = value {
+                args.push(flag.to_string());
+                args.push(value);
+            }
+        };
+        push("-W", self.output_width.map(|value| value.to_string()));
+        push("-H", self.output_height.map(|value| value.to_string()));
+        push("-w", self.width.map(|value| value.to_string()));
+        push("-h", self.height.map(|value| value.to_string()));
+        push("-r", self.refresh_rate.map(|value| value.to_string()));
+        push(
+            "--framerate-limit",
+            self.framerate_limit.map(|value| value.to_string()),
+        );
+        push("-F", self.filter.map(|filter| filter.to_string()));
+        push("-S", self.scaler.map(|scaler| scaler.to_string()));
+        push("--sharpness", self.sharpness.map(|value| value.to_string()));
+        for (flag, set) in [
+            ("-f", self.fullscreen),
+            ("-b", self.borderless),
+            ("--hdr-enabled", self.hdr),
+            ("--mangoapp", self.mangoapp),
+        ] {
+            if set {
+                args.push(flag.to_string());
+            }
+        }
+        args.extend(self.extra.iter().cloned());
+        args
+    }
+
+    /// Describes flag combinations gamescope rejects or ignores.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = vec![];
+        if self.output_width.is_some() != self.output_height.is_some() {
+            problems.push("The output resolution needs both a width and a height".to_string());
+        }
+        if self.width.is_some() != self.height.is_some() {
+            problems.push("The game resolution needs both a width and a height".to_string());
+        }
+        if let (Some(width), Some(height), Some(output_width), Some(output_height)) = (
+            self.width,
+            self.height,
+            self.output_width,
+            self.output_height,
+        ) && (width > output_width || height > output_height)
+        {
+            problems.push("The game resolution is larger than the output resolution".to_string());
+        }
+        if matches!(self.filter, Some(Filter::Fsr | Filter::Nis)) && self.width.is_none() {
+            problems.push("Upscaling with FSR or NIS needs a game resolution".to_string());
+        }
+        if self.sharpness.is_some() && !matches!(self.filter, Some(Filter::Fsr | Filter::Nis)) {
+            problems.push("Sharpness only applies to the FSR and NIS filters".to_string());
+        }
+        if self.sharpness.is_some_and(|sharpness| sharpness > 20) {
+            problems.push("Sharpness ranges from 0 to 20".to_string());
+        }
+        if self.fullscreen && self.borderless {
+            problems.push("Fullscreen and borderless cannot be combined".to_string());
+        }
+        if [
+            self.output_width,
+            self.output_height,
+            self.width,
+            self.height,
+            self.refresh_rate,
+            self.framerate_limit,
+        ]
+        .contains(&Some(0))
+        {
+            problems.push("Resolutions and rates must be greater than 0".to_string());
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        launch_options::split(text)
+    }
+
+    #[test]
+    fn test_parse() {
+        let gamescope = parse(&words(
+            "-W 2560 --output-height=1440 -w 1920 -h 1080 -U -f --expose-wayland",
+        ))
+        .unwrap();
+        assert_eq!(gamescope.output_width, Some(2560));
+        assert_eq!(gamescope.output_height, Some(1440));
+        assert_eq!(gamescope.width, Some(1920));
+        assert_eq!(gamescope.filter, Some(Filter::Fsr));
+        assert!(gamescope.fullscreen);
+        assert_eq!(gamescope.extra, vec!["--expose-wayland"]);
+        assert_eq!(
+            gamescope.args().join(" "),
+            "-W 2560 -H 1440 -w 1920 -h 1080 -F fsr -f --expose-wayland"
+        );
+        assert!(gamescope.validate().is_empty());
+
+        assert!(parse(&words("-W wide")).is_err());
+        assert!(parse(&words("-F bicubic")).is_err());
+        assert!(parse(&words("-r")).is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(
+            from_launch_options("gamescope -- %command%").unwrap(),
+            Some(Gamescope::default())
+        );
+        assert_eq!(from_launch_options("gamemoderun %command%").unwrap(), None);
+
+        let gamescope = Gamescope {
+            output_width: Some(1920),
+            output_height: Some(1080),
+            mangoapp: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            apply(
+                "PROTON_LOG=1 gamemoderun %command% -novid",
+                Some(&gamescope)
+            ),
+            "PROTON_LOG=1 gamescope -W 1920 -H 1080 --mangoapp -- gamemoderun %command% -novid"
+        );
+        assert_eq!(
+            apply("-novid", Some(&gamescope)),
+            "gamescope -W 1920 -H 1080 --mangoapp -- %command% -novid"
+        );
+        assert_eq!(
+            apply(
+                "gamemoderun /usr/bin/gamescope -f -- %command%",
+                Some(&gamescope)
+            ),
+            "gamemoderun /usr/bin/gamescope -W 1920 -H 1080 --mangoapp -- %command%"
+        );
+        assert_eq!(
+            apply("gamescope -W 1920 -H 1080 -- gamemoderun %command%", None),
+            "gamemoderun %command%"
+        );
+        assert_eq!(
+            from_launch_options("gamescope -W 1920 -f mangohud %command%")
+                .unwrap()
+                .and_then(|gamescope| gamescope.output_width),
+            Some(1920)
+        );
+        assert_eq!(
+            apply("gamescope -W 2560 -f mangohud %command%", Some(&gamescope)),
+            "gamescope -W 1920 -H 1080 --mangoapp -- mangohud %command%"
+        );
+        assert_eq!(
+            apply("gamescope -f mangohud %command%", None),
+            "mangohud %command%"
+        );
+        assert_eq!(
+            apply("FOO=$HOME/x %command% | tee log", Some(&gamescope)),
+            "FOO=$HOME/x gamescope -W 1920 -H 1080 --mangoapp -- %command% | tee log"
+        );
+        assert_eq!(
+            apply(
+                "FOO=$HOME/x gamescope -f -- ~/run.sh %command% | tee log",
+                None
+            ),
+            "FOO=$HOME/x ~/run.sh %command% | tee log"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let gamescope = Gamescope {
+            output_width: Some(1280),
+            width: Some(1920),
+            height: Some(1080),
+            sharpness: Some(5),
+            fullscreen: true,
+            borderless: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            gamescope.validate(),
+            vec![
+                "The output resolution needs both a width and a height",
+                "Sharpness only applies to the FSR and NIS filters",
+                "Fullscreen and borderless cannot be combined",
+            ]
+        );
+
+        let gamescope = Gamescope {
+            output_width: Some(1280),
+            output_height: Some(720),
+            width: Some(1920),
+            height: Some(1080),
+            filter: Some(Filter::Fsr),
+            ..Default::default()
+        };
+        assert_eq!(
+            gamescope.validate(),
+            vec!["The game resolution is larger than the output resolution"]
+        );
+    }
+}
@@ -0,0 +1,253 @@
+//! Shell commands run before and after a game, kept in the profile and written to a small script
+//! that Steam runs in front of everything else before `%command%`. Apps with a wrapper script run
+//! them from that script instead.
+
+use crate::config;
+use crate::launch_options::{self, quote};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hooks {
+    /// Run before the game starts
+    #[serde(default)]
+    pub pre: String,
+    /// Run after the game exits
+    #[serde(default)]
+    pub post: String,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.pre.trim().is_empty() && self.post.trim().is_empty()
+    }
+
+    /// The shell lines that run `command` between the hooks, appending their output to `log`.
+    pub fn lines(&self, command: &str, log: &Path) -> Vec<String> {
+        let mut lines = vec![];
+        if !self.is_empty() {
+            lines.push(format!("log={}", quote(&log.to_string_lossy())));
+        }
+        if !self.pre.trim().is_empty() {
+            lines.push("echo \"--- pre $(date)\" >>\"$log\"".to_string());
+            lines.push(format!("{{\n{}\n}} >>\"$log\" 2>&1", self.pre.trim_end()));
+        }
+        if self.post.trim().is_empty() {
+            lines.push(format!("exec {}", command));
+        } else {
+            lines.push(command.to_string());
+            lines.push("status=$?".to_string());
+            lines.push("echo \"--- post $(date) exit $status\" >>\"$log\"".to_string());
+            lines.push(format!("{{\n{}\n}} >>\"$log\" 2>&1", self.post.trim_end()));
+            lines.push("exit $status".to_string());
+        }
+        lines
+    }
+
+    /// The script, which runs the rest of the command line it is given as arguments.
+    pub fn script(&self, appid: u32, log: &Path) -> String {
+        let mut lines = vec![
+            "#!/bin/sh".to_string(),
+            format!(
+                "# Hooks of app {}, generated by {} from the profile",
+                appid,
+                crate::consts::APP_NAME
+            ),
+        ];
+        lines.extend(self.lines("\"$@\"", log));
+        lines.join("\n") + "\n"
+    }
+}
+
+const DIR: &str = "hooks";
+
+pub fn script_path(appid: u32) -> Result<PathBuf, Box<dyn Error>> {
+    config::managed_file(DIR, appid, "sh")
+}
+
+pub fn log_path(appid: u32) -> Result<PathBuf, Box<dyn Error>> {
+    config::managed_file(DIR, appid, "log")
+}
+
+pub fn write(appid: u32, hooks: &Hooks) -> Result<PathBuf, Box<dyn Error>> {
+    write_in(&config::managed_dir(DIR)?, appid, hooks)
+}
+
+fn write_in(dir: &Path, appid: u32, hooks: &Hooks) -> Result<PathBuf, Box<dyn Error>> {
+    let script = dir.join(format!("{}.sh", appid));
+    fs::create_dir_all(dir)?;
+    fs::write(
+        &script,
+        hooks.script(appid, &dir.join(format!("{}.log", appid))),
+    )?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755))?;
+    }
+    debug!("hooks written: {}", script.display());
+    Ok(script)
+}
+
+/// Writes the script of every app with hooks. The scripts of other apps, left by another
+/// profile, are written without hooks, since launch options that Steam still has may run them.
+pub fn materialize(hooks: &BTreeMap<u32, Hooks>) -> Result<(), Box<dyn Error>> {
+    materialize_in(&config::managed_dir(DIR)?, hooks)
+}
+
+fn materialize_in(dir: &Path, hooks: &BTreeMap<u32, Hooks>) -> Result<(), Box<dyn Error>> {
+    for (appid, hooks) in hooks.iter() {
+        write_in(dir, *appid, hooks)?;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().is_some_and(|extension| extension == "sh")
+            && let Some(appid) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str()?.parse::<u32>().ok())
+            && !hooks.contains_key(&appid)
+        {
+            write_in(dir, appid, &Hooks::default())?;
+        }
+    }
+    Ok(())
+}
+
+/// Puts the script first in front of `%command%`, or removes it when `script` is `None`.
+pub fn apply(text: &str, script: Option<&Path>) -> String {
+    let mut launch_options = launch_options::parse(text);
+    if let Ok(dir) = config::managed_dir(DIR) {
+        launch_options
+            .wrappers
+            .retain(|word| Path::new(word).parent() != Some(dir.as_path()));
+    }
+    if let Some(script) = script {
+        launch_options
+            .wrappers
+            .insert(0, script.to_string_lossy().to_string());
+        launch_options.has_command = true;
+    }
+    launch_options.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script() {
+        let log = Path::new("/hooks/1111111.log");
+        let mut hooks = Hooks {
+            pre: "powerprofilesctl set performance\nsystemctl --user stop syncthing".to_string(),
+            post: String::new(),
+        };
+        assert_eq!(
+            hooks.script(1111111, log),
+            "#!/bin/sh\n\
+             # Hooks of app 1111111, generated by Steam OptionX from the profile\n\
+             log=/hooks/1111111.log\n\
+             echo \"--- pre $(date)\" >>\"$log\"\n\
+             {\n\
+             powerprofilesctl set performance\n\
+             systemctl --user stop syncthing\n\
+             } >>\"$log\" 2>&1\n\
+             exec \"$@\"\n"
+        );
+        hooks.pre.clear();
+        hooks.post = "powerprofilesctl set balanced\n".to_string();
+        assert_eq!(
+            hooks.script(1111111, log),
+            "#!/bin/sh\n\
+             # Hooks of app 1111111, generated by Steam OptionX from the profile\n\
+             log=/hooks/1111111.log\n\
+             \"$@\"\n\
+             status=$?\n\
+             echo \"--- post $(date) exit $status\" >>\"$log\"\n\
+             {\n\
+             powerprofilesctl set balanced\n\
+             } >>\"$log\" 2>&1\n\
+             exit $status\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run() {
+        let dir = std::env::temp_dir().join(format!("hooks-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("hooks.sh");
+        let log = dir.join("hooks.log");
+        let hooks = Hooks {
+            pre: "echo before".to_string(),
+            post: "echo after".to_string(),
+        };
+        fs::write(&script, hooks.script(1, &log)).unwrap();
+        let status = std::process::Command::new("sh")
+            .arg(&script)
+            .args(["sh", "-c", "exit 3"])
+            .status()
+            .unwrap();
+        assert_eq!(status.code(), Some(3));
+        let output = fs::read_to_string(&log).unwrap();
+        assert!(output.contains("before\n"));
+        assert!(output.contains("exit 3\nafter\n"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_materialize() {
+        let dir = std::env::temp_dir().join(format!("hooks-materialize-{}", std::process::id()));
+        let hooks = Hooks {
+            pre: "echo before".to_string(),
+            post: String::new(),
+        };
+        materialize_in(&dir, &BTreeMap::from([(1111111, hooks.clone())])).unwrap();
+        materialize_in(&dir, &BTreeMap::from([(2222222, hooks)])).unwrap();
+        let stale = fs::read_to_string(dir.join("1111111.sh")).unwrap();
+        assert!(!stale.contains("echo before"));
+        assert!(stale.ends_with("exec \"$@\"\n"));
+        assert!(
+            fs::read_to_string(dir.join("2222222.sh"))
+                .unwrap()
+                .contains("echo before")
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_apply() {
+        let script = script_path(1111111).unwrap();
+        let text = apply("gamescope -f -- %command% -novid", Some(&script));
+        assert_eq!(
+            text,
+            format!(
+                "{} gamescope -f -- %command% -novid",
+                quote(&script.to_string_lossy())
+            )
+        );
+        assert_eq!(apply(&text, None), "gamescope -f -- %command% -novid");
+        let text = "FOO=$HOME/x ~/run.sh %command% | tee log";
+        let hooked = apply(text, Some(&script));
+        assert_eq!(
+            hooked,
+            format!(
+                "FOO=$HOME/x {} ~/run.sh %command% | tee log",
+                quote(&script.to_string_lossy())
+            )
+        );
+        assert_eq!(apply(&hooked, None), text);
+        assert_eq!(
+            apply("PROTON_LOG=1 %command%", Some(&script)),
+            apply(
+                &apply("PROTON_LOG=1 %command%", Some(&script)),
+                Some(&script)
+            )
+        );
+    }
+}
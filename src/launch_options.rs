@@ -0,0 +1,435 @@
+use std::ops::Range;
+
+pub const COMMAND: &str = "%command%";
+
+/// Launch options split into the parts Steam puts around the game command.
+#[derive(Debug, Default, Clone)]
+pub struct LaunchOptions {
+    pub env: Vec<(String, String)>,
+    /// Commands before `%command%`
+    pub wrappers: Vec<String>,
+    /// Arguments after `%command%`, or all of them without `%command%`
+    pub args: Vec<String>,
+    pub has_command: bool,
+    /// The parsed words as written, so that the unchanged ones are written back the same way
+    written: Vec<Written>,
+}
+
+#[derive(Debug, Clone)]
+struct Written {
+    word: String,
+    /// The text between the word and the one before it
+    gap: String,
+    text: String,
+}
+
+pub fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Shell control and redirection operators such as `;`, `|`, `&&` or `>`.
+pub fn is_operator(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(is_operator_char)
+}
+
+fn is_operator_char(c: char) -> bool {
+    ";|&<>".contains(c)
+}
+
+/// Splits a string into words the way a POSIX shell would, removing quotes. Operators are words
+/// of their own. Returns each word with the byte range it was written in.
+fn tokenize(text: &str) -> Vec<(String, Range<usize>)> {
+    let mut result = vec![];
+    let mut word = String::new();
+    let mut start = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\'' => {
+                start.get_or_insert(i);
+                for (_, c) in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    word.push(c);
+                }
+            }
+            '"' => {
+                start.get_or_insert(i);
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some((_, c @ ('"' | '\\' | '$' | '`'))) => word.push(c),
+                            Some((_, c)) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => word.push('\\'),
+                        },
+                        _ => word.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                start.get_or_insert(i);
+                if let Some((_, c)) = chars.next() {
+                    word.push(c);
+                }
+            }
+            c if c.is_whitespace() || is_operator_char(c) => {
+                if let Some(start) = start.take() {
+                    result.push((std::mem::take(&mut word), start..i));
+                }
+                if is_operator_char(c) {
+                    let mut operator = c.to_string();
+                    let mut end = i + c.len_utf8();
+                    while let Some((i, c)) = chars.next_if(|&(_, c)| is_operator_char(c)) {
+                        operator.push(c);
+                        end = i + c.len_utf8();
+                    }
+                    result.push((operator, i..end));
+                }
+            }
+            c => {
+                start.get_or_insert(i);
+                word.push(c);
+            }
+        }
+    }
+    if let Some(start) = start {
+        result.push((word, start..text.len()));
+    }
+    result
+}
+
+/// Splits a string into words the way a POSIX shell would, removing quotes.
+pub fn split(text: &str) -> Vec<String> {
+    tokenize(text).into_iter().map(|(word, _)| word).collect()
+}
+
+/// Splits a string into words like `split`, keeping each word as it was written.
+pub fn split_written(text: &str) -> Vec<String> {
+    tokenize(text)
+        .into_iter()
+        .map(|(_, range)| text[range].to_string())
+        .collect()
+}
+
+/// Quotes a word so that a POSIX shell reads it back unchanged.
+pub fn quote(word: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "%+,-./:=@_".contains(c);
+    if !word.is_empty() && word.chars().all(safe) {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+pub fn parse(text: &str) -> LaunchOptions {
+    let tokens = tokenize(text);
+    let mut end = 0;
+    let written = tokens
+        .iter()
+        .map(|(word, range)| {
+            let gap = text[end..range.start].to_string();
+            end = range.end;
+            Written {
+                word: word.clone(),
+                gap,
+                text: text[range.clone()].to_string(),
+            }
+        })
+        .collect();
+    let words: Vec<String> = tokens.into_iter().map(|(word, _)| word).collect();
+    let mut result = LaunchOptions {
+        written,
+        ..Default::default()
+    };
+    let Some(command) = words.iter().position(|word| word == COMMAND) else {
+        result.args = words;
+        return result;
+    };
+    result.has_command = true;
+    let mut before = words[..command].iter().peekable();
+    while let Some(word) = before.peek() {
+        match word.split_once('=') {
+            Some((name, value)) if is_env_name(name) => {
+                result.env.push((name.to_string(), value.to_string()));
+                before.next();
+            }
+            _ => break,
+        }
+    }
+    result.wrappers = before.cloned().collect();
+    result.args = words[command + 1..].to_vec();
+    result
+}
+
+/// Like `parse`, with the words as they were written, quotes included, for a shell script to
+/// read them the same way Steam's shell does.
+pub fn parse_written(text: &str) -> LaunchOptions {
+    let mut result = parse(text);
+    let mut written = result.written.iter().map(|written| written.text.clone());
+    for (_, value) in result.env.iter_mut() {
+        if let Some(text) = written.next() {
+            *value = text
+                .split_once('=')
+                .map_or(String::new(), |(_, value)| value.to_string());
+        }
+    }
+    if result.has_command {
+        // Skips `%command%`
+        result.wrappers = written.by_ref().take(result.wrappers.len() + 1).collect();
+        result.wrappers.pop();
+    }
+    result.args = written.collect();
+    result.written.clear();
+    result
+}
+
+/// The command line Steam runs, with `%command%` replaced by the game command quoted for a
+/// POSIX shell. The launch options are kept as written, for the shell to expand variables and
+/// run operators. Without `%command%` they are appended to the game.
+pub fn command_line(text: &str, game: &[String]) -> String {
+    let game: Vec<String> = game.iter().map(|word| quote(word)).collect();
+    let game = game.join(" ");
+    match tokenize(text).into_iter().find(|(word, _)| word == COMMAND) {
+        Some((_, range)) => format!("{}{}{}", &text[..range.start], game, &text[range.end..])
+            .trim()
+            .to_string(),
+        None => format!("{} {}", game, text.trim()).trim().to_string(),
+    }
+}
+
+/// Appends the words unless they are already there in order, continuing words they start with.
+fn append_run(words: &mut Vec<String>, run: &[String]) {
+    if run.is_empty() || words.windows(run.len()).any(|window| window == run) {
+        return;
+    }
+    let overlap = (0..=run.len().min(words.len()))
+        .rev()
+        .find(|&n| words[words.len() - n..] == run[..n])
+        .unwrap_or(0);
+    words.extend(run[overlap..].iter().cloned());
+}
+
+impl LaunchOptions {
+    pub fn env(&self, name: &str) -> Option<&str> {
+        self.env
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Adds what `other` sets to these, its variables replacing ones of the same name. Its
+    /// wrappers go closest to the game and its arguments after the game's, unless already there
+    /// in order.
+    pub fn merge(&mut self, other: &LaunchOptions) {
+        for (name, value) in other.env.iter() {
+            match self.env.iter_mut().find(|(key, _)| key == name) {
+                Some(entry) => entry.1 = value.clone(),
+                None => self.env.push((name.clone(), value.clone())),
+            }
+        }
+        append_run(&mut self.wrappers, &other.wrappers);
+        // The arguments of the game end at an operator such as `;` or `|`
+        let end = self
+            .args
+            .iter()
+            .position(|word| is_operator(word))
+            .unwrap_or(self.args.len());
+        let rest = self.args.split_off(end);
+        append_run(&mut self.args, &other.args);
+        self.args.extend(rest);
+        self.has_command |= other.has_command || !self.env.is_empty() || !self.wrappers.is_empty();
+    }
+}
+
+impl PartialEq for LaunchOptions {
+    fn eq(&self, other: &Self) -> bool {
+        self.env == other.env
+            && self.wrappers == other.wrappers
+            && self.args == other.args
+            && self.has_command == other.has_command
+    }
+}
+
+/// Writes the words back as they were parsed, with the text between them, and quotes the
+/// changed and added ones.
+impl std::fmt::Display for LaunchOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut words: Vec<(String, String)> = vec![];
+        for (name, value) in self.env.iter() {
+            words.push((
+                format!("{}={}", name, value),
+                format!("{}={}", name, quote(value)),
+            ));
+        }
+        words.extend(self.wrappers.iter().map(|word| (word.clone(), quote(word))));
+        if self.has_command {
+            words.push((COMMAND.to_string(), COMMAND.to_string()));
+        }
+        words.extend(self.args.iter().map(|word| (word.clone(), quote(word))));
+        // Lengths of the longest common subsequences of the remaining words and written words,
+        // to write back as many words as they were written as possible
+        let written = &self.written;
+        let mut lengths = vec![vec![0; written.len() + 1]; words.len() + 1];
+        for i in (0..words.len()).rev() {
+            for j in (0..written.len()).rev() {
+                lengths[i][j] = if words[i].0 == written[j].word {
+                    lengths[i + 1][j + 1] + 1
+                } else {
+                    lengths[i + 1][j].max(lengths[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        // Whether the last word written back is the written word before `j`
+        let mut follows = false;
+        let mut text = String::new();
+        while i < words.len() {
+            if j < written.len() && words[i].0 == written[j].word {
+                // Operators written without a space before them keep needing none
+                if follows || (written[j].gap.is_empty() && is_operator(&written[j].text)) {
+                    text.push_str(&written[j].gap);
+                } else if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(&written[j].text);
+                follows = true;
+                i += 1;
+                j += 1;
+            } else if j < written.len() && lengths[i][j + 1] >= lengths[i + 1][j] {
+                follows = false;
+                j += 1;
+            } else {
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(&words[i].1);
+                follows = false;
+                i += 1;
+            }
+        }
+        write!(f, "{}", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let launch_options =
+            parse("PROTON_LOG=1 WINEDLLOVERRIDES=\"dinput8=n,b\" gamemoderun %command% -novid");
+        assert_eq!(launch_options.env("PROTON_LOG"), Some("1"));
+        assert_eq!(launch_options.env("WINEDLLOVERRIDES"), Some("dinput8=n,b"));
+        assert_eq!(launch_options.wrappers, vec!["gamemoderun"]);
+        assert_eq!(launch_options.args, vec!["-novid"]);
+        assert_eq!(
+            launch_options.to_string(),
+            "PROTON_LOG=1 WINEDLLOVERRIDES=\"dinput8=n,b\" gamemoderun %command% -novid"
+        );
+
+        let launch_options = parse("-novid -console");
+        assert!(!launch_options.has_command);
+        assert_eq!(launch_options.args, vec!["-novid", "-console"]);
+        assert_eq!(launch_options.to_string(), "-novid -console");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for text in [
+            "MANGOHUD_CONFIGFILE=$HOME/m.conf mangohud %command%",
+            "gamemoderun %command%; echo done",
+            "%command% -foo | tee /tmp/log",
+            "~/bin/x.sh %command%",
+            "FOO=\"$(cat ~/foo)\"  %command% -a&&echo 'it''s' >>log 2>&1",
+        ] {
+            assert_eq!(parse(text).to_string(), text);
+        }
+        let launch_options = parse("gamemoderun %command%; echo done");
+        assert_eq!(launch_options.wrappers, vec!["gamemoderun"]);
+        assert_eq!(launch_options.args, vec![";", "echo", "done"]);
+
+        let mut launch_options = parse("FOO=$HOME/x ~/bin/x.sh %command%|tee log");
+        launch_options
+            .env
+            .push(("PROTON_LOG".to_string(), "1".to_string()));
+        launch_options.wrappers.push("gamemoderun".to_string());
+        assert_eq!(
+            launch_options.to_string(),
+            "FOO=$HOME/x PROTON_LOG=1 ~/bin/x.sh gamemoderun %command%|tee log"
+        );
+        launch_options.env.clear();
+        launch_options.args.insert(0, "it's".to_string());
+        assert_eq!(
+            launch_options.to_string(),
+            r"~/bin/x.sh gamemoderun %command% 'it'\''s'|tee log"
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut launch_options =
+            parse("PROTON_LOG=1 '/hooks/1.sh' gamescope -f -- %command% -novid");
+        launch_options.merge(&parse("PROTON_LOG=0 gamemoderun %command% -novid -dx11"));
+        assert_eq!(
+            launch_options.to_string(),
+            "PROTON_LOG=0 '/hooks/1.sh' gamescope -f -- gamemoderun %command% -novid -dx11"
+        );
+        let mut launch_options = parse("-novid");
+        launch_options.merge(&parse("PROTON_USE_WINED3D=1 %command%"));
+        assert_eq!(
+            launch_options.to_string(),
+            "PROTON_USE_WINED3D=1 %command% -novid"
+        );
+        launch_options.merge(&parse("-novid"));
+        assert_eq!(
+            launch_options.to_string(),
+            "PROTON_USE_WINED3D=1 %command% -novid"
+        );
+
+        let mut launch_options = parse("FOO=$VAR ~/run.sh %command% -a; echo $?");
+        launch_options.merge(&parse("PROTON_LOG=1 gamemoderun %command% -novid"));
+        assert_eq!(
+            launch_options.to_string(),
+            "FOO=$VAR PROTON_LOG=1 ~/run.sh gamemoderun %command% -a -novid; echo $?"
+        );
+    }
+
+    #[test]
+    fn test_command_line() {
+        let game = vec![
+            "/games/Proton 9.0/proton".to_string(),
+            "waitforexitandrun".to_string(),
+            "/games/The Witcher 3/bin/x64/witcher3.exe".to_string(),
+        ];
+        assert_eq!(
+            command_line("PROTON_LOG=1 gamemoderun %command% -skipintro", &game),
+            "PROTON_LOG=1 gamemoderun '/games/Proton 9.0/proton' waitforexitandrun \
+             '/games/The Witcher 3/bin/x64/witcher3.exe' -skipintro"
+        );
+        assert_eq!(
+            command_line("-skipintro", &game[2..]),
+            "'/games/The Witcher 3/bin/x64/witcher3.exe' -skipintro"
+        );
+        assert_eq!(
+            command_line("FOO=$HOME/x ~/bin/x.sh %command% | tee log", &game[2..]),
+            "FOO=$HOME/x ~/bin/x.sh '/games/The Witcher 3/bin/x64/witcher3.exe' | tee log"
+        );
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("/usr/bin/gamescope"), "/usr/bin/gamescope");
+        assert_eq!(quote("it's here"), r"'it'\''s here'");
+        assert_eq!(split(&quote("it's here")), vec!["it's here"]);
+    }
+}
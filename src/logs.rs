@@ -0,0 +1,203 @@
+//! Logs that explain why a game failed to start: Proton's `steam-<appid>.log`, written with
+//! `PROTON_LOG=1`, and the logs of the Steam client.
+
+use crate::launch_options;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+pub const PROTON_LOG: &str = "PROTON_LOG";
+const PROTON_LOG_DIR: &str = "PROTON_LOG_DIR";
+
+/// Client logs under `Steam/logs` that mention app IDs.
+const STEAM_LOGS: [&str; 4] = [
+    "console-linux.txt",
+    "console_log.txt",
+    "compat_log.txt",
+    "content_log.txt",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+fn env_value<'a>(env: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    env.iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Proton writes the log to the home directory unless `PROTON_LOG_DIR` is set, by the variables
+/// of the app's wrapper script, which are exported last, or by the launch options.
+pub fn proton_log(
+    home: &Path,
+    launch_options: &str,
+    wrapper_env: &[(String, String)],
+    appid: u32,
+) -> PathBuf {
+    let launch_options = launch_options::parse(launch_options);
+    let dir = env_value(wrapper_env, PROTON_LOG_DIR)
+        .or(launch_options.env(PROTON_LOG_DIR))
+        .map(PathBuf::from)
+        .unwrap_or(home.to_path_buf());
+    dir.join(format!("steam-{}.log", appid))
+}
+
+pub fn steam_logs(steam_dir: &Path) -> Vec<PathBuf> {
+    STEAM_LOGS
+        .iter()
+        .map(|name| steam_dir.join("logs").join(name))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// The complete lines in the last `max_bytes` of the file.
+pub fn tail(path: &Path, max_bytes: u64) -> io::Result<Vec<String>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(max_bytes);
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = vec![];
+    file.read_to_end(&mut bytes)?;
+    let text = String::from_utf8_lossy(&bytes);
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    if start > 0 && !lines.is_empty() {
+        // Starts in the middle of a line
+        lines.remove(0);
+    }
+    Ok(lines)
+}
+
+/// Wine prefixes its messages with the channel class, `err:` or `warn:`.
+pub fn severity(line: &str) -> Option<Severity> {
+    let line = line.to_lowercase();
+    if ["err:", "error", "fatal", "failed", "crash"]
+        .iter()
+        .any(|word| line.contains(word))
+    {
+        Some(Severity::Error)
+    } else if ["warn:", "warning"].iter().any(|word| line.contains(word)) {
+        Some(Severity::Warning)
+    } else {
+        None
+    }
+}
+
+/// Whether a client log line is about the app.
+pub fn mentions(line: &str, appid: u32) -> bool {
+    let appid = appid.to_string();
+    line.match_indices(&appid).any(|(i, _)| {
+        let before = line[..i].chars().next_back();
+        let after = line[i + appid.len()..].chars().next();
+        !before.is_some_and(|c| c.is_ascii_digit()) && !after.is_some_and(|c| c.is_ascii_digit())
+    })
+}
+
+/// Whether the variables, of launch options or a wrapper script, turn the log on.
+pub fn is_proton_log_env(env: &[(String, String)]) -> bool {
+    env_value(env, PROTON_LOG).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+pub fn is_proton_log(text: &str) -> bool {
+    is_proton_log_env(&launch_options::parse(text).env)
+}
+
+/// Sets `PROTON_LOG=1` in the variables unless the log is already on, or removes it, keeping the
+/// others where they are.
+pub fn set_proton_log_env(env: &mut Vec<(String, String)>, enabled: bool) {
+    if !enabled {
+        env.retain(|(name, _)| name != PROTON_LOG);
+    } else if !is_proton_log_env(env) {
+        match env.iter_mut().find(|(name, _)| name == PROTON_LOG) {
+            Some(entry) => entry.1 = "1".to_string(),
+            None => env.insert(0, (PROTON_LOG.to_string(), "1".to_string())),
+        }
+    }
+}
+
+/// Sets or removes `PROTON_LOG=1`, keeping the rest of the launch options.
+pub fn set_proton_log(text: &str, enabled: bool) -> String {
+    let mut launch_options = launch_options::parse(text);
+    set_proton_log_env(&mut launch_options.env, enabled);
+    if enabled {
+        launch_options.has_command = true;
+    }
+    launch_options.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail() {
+        let path = Path::new("tests/logs/steam-1111111.log");
+        let lines = tail(path, u64::MAX).unwrap();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(severity(&lines[1]), Some(Severity::Warning));
+        assert_eq!(severity(&lines[3]), Some(Severity::Error));
+        assert_eq!(severity(&lines[4]), None);
+
+        let last = tail(path, lines[4].len() as u64 + 5).unwrap();
+        assert_eq!(last, vec![lines[4].clone()]);
+    }
+
+    #[test]
+    fn test_mentions() {
+        assert!(mentions(
+            "GameAction [AppID 1111111, ActionID 3] : LaunchApp",
+            1111111
+        ));
+        assert!(!mentions("AppID 11111112 state changed", 1111111));
+    }
+
+    #[test]
+    fn test_proton_log() {
+        let home = Path::new("/home/user");
+        assert_eq!(
+            proton_log(home, "%command%", &[], 1111111),
+            PathBuf::from("/home/user/steam-1111111.log")
+        );
+        assert_eq!(
+            proton_log(home, "PROTON_LOG_DIR=/tmp/logs %command%", &[], 1111111),
+            PathBuf::from("/tmp/logs/steam-1111111.log")
+        );
+        let wrapper_env = vec![(PROTON_LOG_DIR.to_string(), "/wrapped".to_string())];
+        assert_eq!(
+            proton_log(
+                home,
+                "PROTON_LOG_DIR=/tmp/logs %command%",
+                &wrapper_env,
+                1111111
+            ),
+            PathBuf::from("/wrapped/steam-1111111.log")
+        );
+
+        let text = set_proton_log("gamemoderun %command% -novid", true);
+        assert_eq!(text, "PROTON_LOG=1 gamemoderun %command% -novid");
+        assert!(is_proton_log(&text));
+        assert_eq!(set_proton_log(&text, false), "gamemoderun %command% -novid");
+        assert!(!is_proton_log("PROTON_LOG=0 %command%"));
+        let text = "FOO=$HOME/x PROTON_LOG=0 ~/run.sh %command% | tee log";
+        assert_eq!(
+            set_proton_log(text, true),
+            "FOO=$HOME/x PROTON_LOG=1 ~/run.sh %command% | tee log"
+        );
+        assert_eq!(
+            set_proton_log(text, false),
+            "FOO=$HOME/x ~/run.sh %command% | tee log"
+        );
+        assert_eq!(
+            set_proton_log("FOO=$HOME/x %command%|tee log", true),
+            "PROTON_LOG=1 FOO=$HOME/x %command%|tee log"
+        );
+
+        let mut env = wrapper_env;
+        set_proton_log_env(&mut env, true);
+        assert!(is_proton_log_env(&env));
+        set_proton_log_env(&mut env, false);
+        assert_eq!(env.len(), 1);
+    }
+}
@@ -1,44 +1,65 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod app_history;
+mod appinfo_vdf;
 mod appmanifest_acf;
+mod compatdata;
+mod completion;
+mod config;
+mod config_vdf;
 mod consts;
+mod dxvk;
+mod env_catalog;
+mod fsutil;
+mod fuzzy;
+mod gamescope;
+mod hooks;
+mod launch_options;
 mod libraryfolders_vdf;
 mod localconfig_vdf;
+mod logs;
+mod mangohud;
+mod profile;
+mod profile_history;
+mod protondb;
+mod query;
+mod schema;
+mod shadercache;
+mod team;
+mod ui;
+mod userdata;
+mod wrapper;
 
 use directories::BaseDirs;
 use eframe::egui;
 use egui_extras::{Column, TableBuilder};
 use log::{debug, warn};
+use profile::Profile;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error;
 use std::fs;
 use std::path::PathBuf;
-use std::str::FromStr;
 use strum_macros::{Display, EnumString};
 
-#[derive(Debug, Default, Serialize, Deserialize)]
-struct Config {
-    steam_config: Option<String>,
-    default_launch_options: Option<String>,
-    app_sort: Option<String>,
-    protondb: Option<bool>,
-}
-
-#[derive(Debug, Default, Serialize, Deserialize)]
-struct Profile {
-    options: Option<BTreeMap<String, String>>,
-    locks: Option<Vec<String>>,
-}
-
 #[derive(Debug)]
 struct App {
     name: String,
     launch_options: String,
     lock: bool,
+    library: PathBuf,
+    /// Directory under `steamapps/common`
+    installdir: String,
 }
 
-#[derive(Default, PartialEq, Clone, Display, EnumString)]
+const FILTER_HELP: &str = "Words match app names, notes and tags.
+id:570  name:\"Half-Life 2\"  name:/^portal/
+has:options  empty:options  locked:true
+contains:gamescope  env:PROTON_LOG  env:DXVK_ASYNC=1
+library:/mnt/games  tag:proton  note:crash  tier:gold
+Combine with AND, OR, NOT (or -) and parentheses.";
+
+#[derive(Debug, Default, PartialEq, Clone, Display, EnumString, Serialize, Deserialize)]
 enum AppSort {
     #[default]
     #[strum(serialize = "⬆ App ID")]
@@ -49,6 +70,17 @@ enum AppSort {
     NameAscending,
     #[strum(serialize = "⬇ App Name")]
     NameDescending,
+    #[strum(serialize = "🔍 Relevance")]
+    Relevance,
+    #[strum(serialize = "⬇ Shader Cache")]
+    ShaderCacheDescending,
+    #[strum(serialize = "⬇ ProtonDB Tier")]
+    TierDescending,
+}
+
+enum Pending {
+    Close,
+    SwitchProfile(String),
 }
 
 #[derive(Default)]
@@ -62,14 +94,159 @@ struct EguiApp {
     protondb: bool,
     url: String,
     locks: BTreeMap<u32, bool>,
+    accounts: ui::accounts::AccountsWindow,
+    git_history: bool,
+    profile_history: ui::profile_history::ProfileHistoryWindow,
+    team: ui::team::TeamWindow,
+    profile: String,
+    profiles: ui::profiles::ProfileSwitcher,
+    warnings: Vec<String>,
+    notes: BTreeMap<u32, String>,
+    tags: BTreeMap<u32, Vec<String>>,
+    notes_window: ui::notes::NotesWindow,
+    hooks: BTreeMap<u32, hooks::Hooks>,
+    hooks_window: ui::hooks::HooksWindow,
+    saved_filters: BTreeMap<String, String>,
+    saved_filter_name: String,
+    view: Option<AppView>,
+    palette: ui::palette::CommandPalette,
+    focus_filter: bool,
+    selected: Option<u32>,
+    /// Apps picked with Ctrl+click for bulk actions
+    selection: BTreeSet<u32>,
+    /// App whose launch options are being edited, with the value to restore on Escape
+    editing: Option<(u32, String)>,
+    /// What waits for the unsaved changes to be saved or discarded
+    confirm: Option<Pending>,
+    allow_close: bool,
+    preview: ui::command_preview::CommandPreview,
+    gamescope: ui::gamescope::GamescopeWindow,
+    mangohud: ui::mangohud::MangoHudWindow,
+    dxvk: ui::dxvk::DxvkWindow,
+    wrapper: ui::wrapper::WrapperWindow,
+    logs: ui::logs::LogWindow,
+    app_history: ui::app_history::AppHistoryWindow,
+    /// How unsaved launch options got their value, recorded in the app history on save
+    pending_sources: BTreeMap<u32, app_history::Source>,
+    /// The highlighted completion of the launch options being edited
+    completion: usize,
+    /// Words to complete in the launch options being edited, collected when editing starts
+    completion_words: Option<(u32, completion::Words)>,
+    compatdata: ui::compatdata::CompatdataWindow,
+    shader_cache: ui::shadercache::ShaderCacheWindow,
+    /// Bytes of shader cache per app, filled in by a background scan
+    shader_cache_sizes: BTreeMap<u32, u64>,
+    protondb_import: ui::protondb::ProtonDbImport,
+    /// Tiers and suggested launch options from the imported ProtonDB reports
+    protondb_reports: BTreeMap<u32, protondb::AppReports>,
+}
+
+/// Everything the toolbar does, so it can also be reached with shortcuts and the command palette.
+#[derive(Debug, Clone, PartialEq)]
+enum Action {
+    OpenFile,
+    Refresh,
+    Save,
+    Clear,
+    Restore,
+    FocusFilter,
+    Accounts,
+    Team,
+    Gamescope,
+    OrphanedPrefixes,
+    ShaderCaches,
+    ImportProtonDb,
+    Palette,
+    Profile(String),
+    Filter(String),
+    Sort(AppSort),
+}
+
+const SHORTCUTS: [(egui::KeyboardShortcut, Action); 5] = [
+    (
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::S),
+        Action::Save,
+    ),
+    // Before Refresh, which would also match with Shift held
+    (
+        egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+            egui::Key::R,
+        ),
+        Action::Restore,
+    ),
+    (
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::R),
+        Action::Refresh,
+    ),
+    (
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::F),
+        Action::FocusFilter,
+    ),
+    (
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::P),
+        Action::Palette,
+    ),
+];
+
+impl Action {
+    fn label(&self) -> String {
+        match self {
+            Action::OpenFile => "🗁 Open file…".to_string(),
+            Action::Refresh => "⟳ Refresh".to_string(),
+            Action::Save => "💾 Save".to_string(),
+            Action::Clear => "🗑 Clear".to_string(),
+            Action::Restore => "🔄 Restore".to_string(),
+            Action::FocusFilter => "🔍 Filter apps".to_string(),
+            Action::Accounts => "👥 Accounts".to_string(),
+            Action::Team => "🤝 Team".to_string(),
+            Action::Gamescope => "🖥 Gamescope for selected apps".to_string(),
+            Action::OrphanedPrefixes => "🍷 Orphaned prefixes".to_string(),
+            Action::ShaderCaches => "💾 Shader caches".to_string(),
+            Action::ImportProtonDb => "⚛ Import ProtonDB reports…".to_string(),
+            Action::Palette => "Command palette".to_string(),
+            Action::Profile(name) => format!("Profile: {}", name),
+            Action::Filter(name) => format!("⭐ Filter: {}", name),
+            Action::Sort(sort) => format!("Sort: {}", sort),
+        }
+    }
+
+    fn shortcut(&self, ctx: &egui::Context) -> String {
+        SHORTCUTS
+            .iter()
+            .find(|(_, action)| action == self)
+            .map(|(shortcut, _)| ctx.format_shortcut(shortcut))
+            .unwrap_or_default()
+    }
+}
+
+fn editor_id(appid: u32) -> egui::Id {
+    egui::Id::new(("launch_options", appid))
+}
+
+/// Apps that pass the filter in display order, rebuilt only when the filter, the sort or the
+/// apps change.
+#[derive(Default)]
+struct AppView {
+    filter: String,
+    sort: AppSort,
+    rows: Vec<u32>,
+    words: Vec<String>,
+    error: Option<String>,
 }
 
 fn main() -> eframe::Result {
     env_logger::init();
 
-    let config: Config = confy::load(consts::CODE_NAME, None).unwrap_or_default();
+    let config = config::load();
     debug!("{} config loaded", consts::CODE_NAME);
 
+    // `steam-optionx list [QUERY]` prints the apps matching a filter query or saved filter
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "list") {
+        std::process::exit(list(&config, &args[1..].join(" ")));
+    }
+
     let steam_config = config.steam_config;
     let apps = if let Some(localconfig_vdf_path) = &steam_config {
         debug!("localconfig.vdf: {}", localconfig_vdf_path);
@@ -82,14 +259,12 @@ fn main() -> eframe::Result {
     let default_launch_options = config.default_launch_options.unwrap_or_default();
     debug!("default_launch_options: {}", default_launch_options);
 
-    let app_sort = config.app_sort;
-    let app_sort = if let Some(sort) = &app_sort {
-        AppSort::from_str(sort.as_str()).unwrap_or_default()
-    } else {
-        AppSort::default()
-    };
+    let app_sort = config.app_sort.unwrap_or_default();
     debug!("app_sort: {}", app_sort);
 
+    let saved_filters = config.saved_filters.unwrap_or_default();
+    debug!("saved_filters: {:?}", saved_filters);
+
     let protondb = config.protondb.unwrap_or_default();
     debug!("protondb: {}", protondb);
     let url = if protondb {
@@ -98,6 +273,23 @@ fn main() -> eframe::Result {
         "https://store.steampowered.com/app/".to_string()
     };
 
+    let git_history = config.git_history.unwrap_or_default();
+    debug!("git_history: {}", git_history);
+
+    let profile = config.profile.unwrap_or(profile::DEFAULT.to_string());
+    debug!("profile: {}", profile);
+    let mut profiles = ui::profiles::ProfileSwitcher::default();
+    profiles.reload();
+    let active_profile = profile::load(&profile);
+    let notes = active_profile.notes();
+    let tags = active_profile.tags();
+    let hooks = active_profile.hooks();
+    let protondb_reports = protondb::load().by_appid();
+    debug!("protondb_reports: {}", protondb_reports.len());
+    if let Err(err) = hooks::materialize(&hooks) {
+        warn!("error writing hooks: {}", err);
+    }
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_icon(
             eframe::icon_data::from_png_bytes(&include_bytes!("../assets/icon.png")[..])
@@ -116,23 +308,101 @@ fn main() -> eframe::Result {
                 app_sort: app_sort,
                 protondb: protondb,
                 url: url,
+                git_history,
+                profile,
+                profiles,
+                notes,
+                tags,
+                hooks,
+                protondb_reports,
+                saved_filters,
                 ..Default::default()
             }))
         }),
     )
 }
 
+/// Prints the apps matching a filter query or the name of a saved filter without writing
+/// anything, returning the exit code.
+fn list(config: &config::Config, filter: &str) -> i32 {
+    let filter = config
+        .saved_filters
+        .as_ref()
+        .and_then(|saved_filters| saved_filters.get(filter))
+        .map_or(filter, |saved| saved.as_str());
+    let query = match query::parse(filter) {
+        Ok(query) => query,
+        Err(err) => {
+            eprintln!("Invalid filter: {}", err);
+            return 2;
+        }
+    };
+    let apps = config
+        .steam_config
+        .as_ref()
+        .and_then(read_apps)
+        .unwrap_or_default();
+    let profile = profile::load(config.profile.as_deref().unwrap_or(profile::DEFAULT));
+    let notes = profile.notes();
+    let tags = profile.tags();
+    let protondb_reports = protondb::load().by_appid();
+    for (appid, app) in apps.iter() {
+        if query.matches(&query::Candidate {
+            appid: *appid,
+            name: &app.name,
+            launch_options: &app.launch_options,
+            lock: app.lock,
+            library: &app.library,
+            notes: notes.get(appid),
+            tags: tags.get(appid),
+            tier: protondb_reports.get(appid).map(|reports| reports.tier),
+        }) {
+            println!("{}\t{}\t{}", appid, app.name, app.launch_options);
+        }
+    }
+    0
+}
+
 fn update_apps(localconfig_vdf_path: &String) -> Option<BTreeMap<u32, App>> {
     backup_file(localconfig_vdf_path, ".orig").expect("Error backup failed");
+    let apps = read_apps(localconfig_vdf_path)?;
+    if let Err(err) = app_history::record_all(
+        localconfig_vdf_path,
+        apps.iter().map(|(appid, app)| {
+            (
+                *appid,
+                app.launch_options.as_str(),
+                app_history::Source::External,
+            )
+        }),
+    ) {
+        warn!("error recording app history: {}", err);
+    }
+    Some(apps)
+}
+
+fn read_apps(localconfig_vdf_path: &String) -> Option<BTreeMap<u32, App>> {
     let properties =
         localconfig_vdf::read_launch_options(localconfig_vdf_path).unwrap_or(BTreeMap::default());
-    let profile: Profile = confy::load(consts::CODE_NAME, "profile").unwrap_or_default();
+    let profile = profile::load(&profile::active());
 
     let libraryfolders_vdf_path = config_dir(localconfig_vdf_path);
     let apps = libraryfolders_vdf::read_installed_apps(libraryfolders_vdf_path.clone())
         .unwrap_or_default();
-    let app_names: BTreeMap<u32, String> =
-        appmanifest_acf::read_app_names(apps).unwrap_or(properties.clone());
+    let mut libraries: BTreeMap<u32, PathBuf> = BTreeMap::new();
+    for (library, appids) in apps.0.iter().zip(apps.1.iter()) {
+        for appid in appids.iter() {
+            if let Ok(appid) = appid.parse::<u32>() {
+                libraries.insert(appid, library.clone());
+            }
+        }
+    }
+    let manifests = appmanifest_acf::read_app_names(&apps);
+    for err in manifests.errors.into_iter() {
+        schema::push_warning(format!("Error reading app manifest {}", err));
+    }
+    let app_names = manifests.names;
+    let installdirs = manifests.installdirs;
     let appids: Vec<String> = app_names
         .clone()
         .into_keys()
@@ -140,7 +410,16 @@ fn update_apps(localconfig_vdf_path: &String) -> Option<BTreeMap<u32, App>> {
         .collect();
     let locks: BTreeMap<u32, bool> = get_locks(appids.clone(), profile.locks.unwrap_or_default());
 
-    Some(get_installed_apps(&appids, &properties, &app_names, &locks).unwrap_or_default())
+    let apps = get_installed_apps(
+        &appids,
+        &properties,
+        &app_names,
+        &locks,
+        &libraries,
+        &installdirs,
+    )
+    .unwrap_or_default();
+    Some(apps)
 }
 
 fn get_locks(appids: Vec<String>, locks: Vec<String>) -> BTreeMap<u32, bool> {
@@ -165,6 +444,22 @@ fn update_launch_options(
     }
 }
 
+fn restore_launch_options(
+    profile: &Profile,
+    apps: &Option<BTreeMap<u32, App>>,
+    all_launch_options: &mut BTreeMap<u32, String>,
+) {
+    if let Some(options) = &profile.options {
+        let mut profile: BTreeMap<u32, String> = BTreeMap::new();
+        for (key, value) in options.iter() {
+            profile.insert(key.parse::<u32>().unwrap(), value.clone());
+        }
+        *all_launch_options = profile.clone();
+    } else if let Some(apps) = apps {
+        update_launch_options(apps, all_launch_options)
+    }
+}
+
 fn backup_file(picked_path: &String, ext: &str) -> Result<(), Box<dyn Error>> {
     let backup_path = PathBuf::from(picked_path.clone() + ext);
     match ext {
@@ -206,11 +501,20 @@ fn config_dir(localconfig_vdf_path: &String) -> PathBuf {
     path
 }
 
+fn steam_dir(localconfig_vdf_path: &String) -> PathBuf {
+    let mut path = config_dir(localconfig_vdf_path);
+    path.pop();
+    path.pop();
+    path
+}
+
 fn get_installed_apps(
     appids: &Vec<String>,
     properties: &BTreeMap<u32, String>,
     app_names: &BTreeMap<u32, String>,
     locks: &BTreeMap<u32, bool>,
+    libraries: &BTreeMap<u32, PathBuf>,
+    installdirs: &BTreeMap<u32, String>,
 ) -> Result<BTreeMap<u32, App>, Box<dyn Error>> {
     let mut apps = BTreeMap::new();
     for appid in appids.iter() {
@@ -222,6 +526,8 @@ fn get_installed_apps(
                 name: app_name.clone(),
                 launch_options: launch_options,
                 lock: lock,
+                library: libraries.get(&appid).cloned().unwrap_or_default(),
+                installdir: installdirs.get(&appid).cloned().unwrap_or_default(),
             };
             apps.insert(appid, game);
         }
@@ -229,33 +535,780 @@ fn get_installed_apps(
     Ok(apps)
 }
 
-fn is_filtered(filter: &String, app_name: &String) -> bool {
-    filter.is_empty()
-        || app_name
-            .to_lowercase()
-            .contains(&filter.trim().to_lowercase())
-}
-
-fn sort_apps(sort: AppSort, apps: &BTreeMap<u32, App>) -> Vec<(&u32, &App)> {
+fn sort_apps<'a>(
+    sort: AppSort,
+    apps: &'a BTreeMap<u32, App>,
+    scores: &BTreeMap<u32, i64>,
+) -> Vec<(&'a u32, &'a App)> {
     match sort {
-        AppSort::IdAscending => apps.into_iter().collect(),
+        // Sizes and tiers are not known here, the view is sorted by them afterwards
+        AppSort::IdAscending | AppSort::ShaderCacheDescending | AppSort::TierDescending => {
+            apps.into_iter().collect()
+        }
         AppSort::IdDescending => apps.into_iter().rev().collect(),
         AppSort::NameAscending => {
             let mut v = apps.into_iter().collect::<Vec<(&u32, &App)>>();
-            v.sort_by(|a, b| a.1.name.to_lowercase().cmp(&b.1.name.to_lowercase()));
+            v.sort_by_cached_key(|a| a.1.name.to_lowercase());
             v
         }
         AppSort::NameDescending => {
             let mut v = apps.into_iter().collect::<Vec<(&u32, &App)>>();
-            v.sort_by(|a, b| b.1.name.to_lowercase().cmp(&a.1.name.to_lowercase()));
+            v.sort_by_cached_key(|a| std::cmp::Reverse(a.1.name.to_lowercase()));
             v
         }
+        AppSort::Relevance => {
+            let mut v = apps.iter().collect::<Vec<(&u32, &App)>>();
+            v.sort_by(|a, b| {
+                let score = |appid| scores.get(appid).unwrap_or(&0);
+                score(b.0)
+                    .cmp(score(a.0))
+                    .then(a.1.name.to_lowercase().cmp(&b.1.name.to_lowercase()))
+            });
+            v
+        }
+    }
+}
+
+/// Lays out an app name with the characters matched by the filter highlighted.
+fn highlight(ui: &egui::Ui, name: &str, words: &[String]) -> egui::text::LayoutJob {
+    let positions: BTreeSet<usize> = words
+        .iter()
+        .filter_map(|word| fuzzy::find(word, name))
+        .flat_map(|found| found.positions)
+        .collect();
+    let font_id = egui::TextStyle::Body.resolve(ui.style());
+    let mut job = egui::text::LayoutJob::default();
+    for (i, c) in name.chars().enumerate() {
+        let mut format = egui::TextFormat::simple(font_id.clone(), egui::Color32::PLACEHOLDER);
+        if positions.contains(&i) {
+            format.background = ui.visuals().selection.bg_fill;
+        }
+        job.append(c.encode_utf8(&mut [0; 4]), 0.0, format);
+    }
+    job
+}
+
+/// What is known about each app besides Steam's data, for filter queries to match.
+struct AppLookups<'a> {
+    locks: &'a BTreeMap<u32, bool>,
+    notes: &'a BTreeMap<u32, String>,
+    tags: &'a BTreeMap<u32, Vec<String>>,
+    protondb_reports: &'a BTreeMap<u32, protondb::AppReports>,
+}
+
+fn filter_apps(
+    filter: &str,
+    sort: AppSort,
+    apps: &BTreeMap<u32, App>,
+    all_launch_options: &BTreeMap<u32, String>,
+    lookups: &AppLookups,
+) -> AppView {
+    let query = query::parse(filter);
+    let words: Vec<String> = query
+        .as_ref()
+        .map(|query| query.words().into_iter().map(str::to_string).collect())
+        .unwrap_or_default();
+    let mut scores: BTreeMap<u32, i64> = BTreeMap::new();
+    for (appid, properties) in apps.iter() {
+        if let Ok(query) = &query
+            && !query.matches(&query::Candidate {
+                appid: *appid,
+                name: &properties.name,
+                launch_options: all_launch_options
+                    .get(appid)
+                    .unwrap_or(&properties.launch_options),
+                lock: *lookups.locks.get(appid).unwrap_or(&properties.lock),
+                library: &properties.library,
+                notes: lookups.notes.get(appid),
+                tags: lookups.tags.get(appid),
+                tier: lookups
+                    .protondb_reports
+                    .get(appid)
+                    .map(|reports| reports.tier),
+            })
+        {
+            continue;
+        }
+        let score = words
+            .iter()
+            .filter_map(|word| fuzzy::find(word, &properties.name))
+            .map(|found| found.score)
+            .sum();
+        scores.insert(*appid, score);
+    }
+    let rows = sort_apps(sort.clone(), apps, &scores)
+        .into_iter()
+        .map(|(appid, _)| *appid)
+        .filter(|appid| scores.contains_key(appid))
+        .collect();
+    AppView {
+        filter: filter.to_string(),
+        sort,
+        rows,
+        words,
+        error: query.err(),
+    }
+}
+
+impl EguiApp {
+    fn open_file(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("text", &["vdf"])
+            .set_directory(userdata_dir())
+            .pick_file()
+        {
+            let localconfig_vdf_path = path.to_str().unwrap_or_default().to_owned();
+            let mut config = config::load();
+            config.steam_config = Some(localconfig_vdf_path.clone());
+            config::store(config);
+            self.apps = update_apps(&localconfig_vdf_path);
+            self.steam_config = Some(localconfig_vdf_path);
+            self.view = None;
+        }
+    }
+
+    /// Returns whether the apps were reloaded.
+    fn refresh(&mut self) -> bool {
+        let Some(localconfig_vdf_path) = &self.steam_config else {
+            return false;
+        };
+        let mut config = config::load();
+        config.steam_config = Some(localconfig_vdf_path.clone());
+        config::store(config);
+        self.apps = update_apps(localconfig_vdf_path);
+        if let Some(apps) = &self.apps {
+            update_launch_options(apps, &mut self.all_launch_options)
+        }
+        self.view = None;
+        self.preview = Default::default();
+        self.shader_cache.invalidate();
+        true
+    }
+
+    /// Returns whether the launch options were written.
+    fn save(&mut self) -> bool {
+        let Some(picked_path) = self.steam_config.clone() else {
+            return false;
+        };
+        let mut config = config::load();
+        let previous_default_launch_options =
+            config.default_launch_options.take().unwrap_or_default();
+
+        let mut profile: Profile = Profile::default();
+        let mut locks: Vec<String> = Vec::new();
+        for (key, value) in self.locks.iter() {
+            if *value {
+                locks.push(key.to_string());
+            }
+        }
+        profile.locks = Some(locks.clone());
+        profile.set_notes(&self.notes, &self.tags);
+        profile.set_hooks(&self.hooks);
+        if !self.default_launch_options.trim().is_empty() {
+            for (appid, launch_options) in self.all_launch_options.iter_mut() {
+                if (launch_options.is_empty() && !locks.contains(&appid.clone().to_string()))
+                    || launch_options == &previous_default_launch_options
+                {
+                    *launch_options = self.default_launch_options.clone();
+                }
+            }
+        }
+        let mut options: BTreeMap<String, String> = BTreeMap::new();
+        for (key, value) in self.all_launch_options.iter() {
+            options.insert(key.to_string(), value.clone());
+        }
+        let message = self.git_history.then(|| {
+            let previous = profile::load(&self.profile);
+            let app_names: BTreeMap<u32, String> = self
+                .apps
+                .iter()
+                .flatten()
+                .map(|(appid, app)| (*appid, app.name.clone()))
+                .collect();
+            profile_history::message(
+                &self.profile,
+                &previous.options.unwrap_or_default(),
+                &options,
+                &app_names,
+            )
+        });
+        profile.options = Some(options);
+        if let Err(err) = profile::store(&self.profile, &profile) {
+            schema::push_warning(format!("Launch options were not saved: {}", err));
+            return false;
+        }
+        config.default_launch_options = Some(self.default_launch_options.clone());
+        config::store(config);
+        if let Err(err) = hooks::materialize(&self.hooks) {
+            warn!("error writing hooks: {}", err);
+        }
+        if let Some(message) = message
+            && let Err(err) = profile_history::repository_dir()
+                .and_then(|dir| profile_history::commit(&dir, &self.profile, &profile, &message))
+        {
+            warn!("error committing profile: {}", err);
+        }
+        backup_file(&picked_path, ".bak").expect("Error backup failed");
+        localconfig_vdf::write_launch_options(&picked_path, &self.all_launch_options)
+            .expect("Error failed to write launch options to config");
+        let pending = std::mem::take(&mut self.pending_sources);
+        if let Err(err) = app_history::record_all(
+            &picked_path,
+            self.all_launch_options
+                .iter()
+                .map(|(appid, launch_options)| {
+                    (
+                        *appid,
+                        launch_options.as_str(),
+                        pending
+                            .get(appid)
+                            .copied()
+                            .unwrap_or(app_history::Source::Save),
+                    )
+                }),
+        ) {
+            warn!("error recording app history: {}", err);
+        }
+        self.regenerate_wrappers();
+        for (appid, app) in self.apps.iter_mut().flatten() {
+            if let Some(launch_options) = self.all_launch_options.get(appid) {
+                app.launch_options = launch_options.clone();
+            }
+        }
+        self.view = None;
+        true
+    }
+
+    /// Generates the wrapper scripts in the launch options again, with the current hooks.
+    fn regenerate_wrappers(&self) {
+        for (appid, launch_options) in self.all_launch_options.iter() {
+            if wrapper::is_wrapped(launch_options, *appid)
+                && let Err(err) = wrapper::regenerate(
+                    *appid,
+                    self.hooks.get(appid).unwrap_or(&hooks::Hooks::default()),
+                )
+            {
+                warn!("error regenerating wrapper script: {}", err);
+            }
+        }
+    }
+
+    /// Whether the launch options of an app differ from the ones in localconfig.vdf.
+    fn is_dirty(&self, appid: &u32) -> bool {
+        match (
+            self.apps.as_ref().and_then(|apps| apps.get(appid)),
+            self.all_launch_options.get(appid),
+        ) {
+            (Some(app), Some(launch_options)) => app.launch_options != *launch_options,
+            _ => false,
+        }
+    }
+
+    fn dirty_count(&self) -> usize {
+        self.apps
+            .iter()
+            .flatten()
+            .filter(|(appid, _)| self.is_dirty(appid))
+            .count()
+    }
+
+    /// Whether the notes, tags, hooks or locks differ from the ones in the active profile.
+    fn is_profile_dirty(&self) -> bool {
+        let saved = profile::load(&self.profile);
+        let mut current = Profile::default();
+        current.set_notes(&self.notes, &self.tags);
+        current.set_hooks(&self.hooks);
+        let locks: BTreeSet<String> = self
+            .locks
+            .iter()
+            .filter(|(_, lock)| **lock)
+            .map(|(appid, _)| appid.to_string())
+            .collect();
+        let saved_locks: BTreeSet<String> = saved
+            .locks
+            .iter()
+            .flatten()
+            .filter(|appid| {
+                appid
+                    .parse()
+                    .is_ok_and(|appid| self.locks.contains_key(&appid))
+            })
+            .cloned()
+            .collect();
+        current.notes() != saved.notes()
+            || current.tags() != saved.tags()
+            || current.hooks() != saved.hooks()
+            || locks != saved_locks
+    }
+
+    /// Asks before closing the window or switching profiles while changes are unsaved.
+    fn confirm_unsaved(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| i.viewport().close_requested())
+            && !self.allow_close
+            && (self.dirty_count() > 0 || self.is_profile_dirty())
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.confirm = Some(Pending::Close);
+        }
+        let Some(pending) = &self.confirm else {
+            return;
+        };
+        let action = match pending {
+            Pending::Close => "quit".to_string(),
+            Pending::SwitchProfile(name) => format!("switch to {}", name),
+        };
+        let mut proceed = false;
+        let modal = egui::Modal::new(egui::Id::new("confirm_unsaved")).show(ctx, |ui| {
+            ui.heading("Unsaved changes");
+            let dirty = self.dirty_count();
+            if dirty > 0 {
+                ui.label(format!(
+                    "Launch options of {} app(s) have not been saved.",
+                    dirty
+                ));
+            }
+            if self.is_profile_dirty() {
+                ui.label(format!(
+                    "Notes, tags, hooks or locks of profile {} have not been saved.",
+                    self.profile
+                ));
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button(format!("💾 Save and {}", action)).clicked() {
+                    proceed = self.save();
+                    if !proceed {
+                        self.confirm = None;
+                    }
+                }
+                if ui.button(format!("🗑 Discard and {}", action)).clicked() {
+                    proceed = true;
+                }
+                if ui.button("✖ Cancel").clicked() {
+                    self.confirm = None;
+                }
+            });
+        });
+        if modal.should_close() {
+            self.confirm = None;
+        }
+        if proceed {
+            match self.confirm.take() {
+                Some(Pending::Close) => {
+                    self.allow_close = true;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+                Some(Pending::SwitchProfile(name)) => self.load_profile(name),
+                None => {}
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        for launch_options in self.all_launch_options.values_mut() {
+            launch_options.clear();
+        }
+        self.view = None;
+    }
+
+    fn restore(&mut self) {
+        let profile = profile::load(&self.profile);
+        restore_launch_options(&profile, &self.apps, &mut self.all_launch_options);
+        self.view = None;
+    }
+
+    /// Switches to another profile, first asking what to do with unsaved changes. Those of a
+    /// deleted profile are dropped.
+    fn switch_profile(&mut self, name: String) {
+        if profile::list().contains(&self.profile)
+            && (self.dirty_count() > 0 || self.is_profile_dirty())
+        {
+            self.confirm = Some(Pending::SwitchProfile(name));
+        } else {
+            self.load_profile(name);
+        }
+    }
+
+    fn load_profile(&mut self, name: String) {
+        profile::set_active(&name);
+        let profile = profile::load(&name);
+        restore_launch_options(&profile, &self.apps, &mut self.all_launch_options);
+        self.notes = profile.notes();
+        self.tags = profile.tags();
+        self.hooks = profile.hooks();
+        if let Err(err) = hooks::materialize(&self.hooks) {
+            warn!("error writing hooks: {}", err);
+        }
+        self.regenerate_wrappers();
+        let locks = profile.locks.unwrap_or_default();
+        for (appid, lock) in self.locks.iter_mut() {
+            *lock = locks.contains(&appid.to_string());
+        }
+        self.profile = name;
+        self.view = None;
+    }
+
+    /// Words of the other apps and of the launch options saved in the other profiles.
+    fn completion_words(&self, appid: u32) -> completion::Words {
+        let presets: Vec<String> = profile::list()
+            .iter()
+            .filter(|name| **name != self.profile)
+            .filter_map(|name| profile::load(name).options)
+            .flat_map(|options| options.into_values())
+            .collect();
+        completion::Words {
+            used: completion::used_words(
+                self.all_launch_options
+                    .iter()
+                    .filter(|(other, _)| **other != appid)
+                    .map(|(_, launch_options)| launch_options)
+                    .chain([&self.default_launch_options]),
+            ),
+            presets: completion::used_words(presets.iter()),
+        }
+    }
+
+    /// Library folders from libraryfolders.vdf.
+    fn libraries(&self) -> Vec<PathBuf> {
+        self.steam_config
+            .as_ref()
+            .and_then(|localconfig_vdf_path| {
+                libraryfolders_vdf::read_installed_apps(config_dir(localconfig_vdf_path)).ok()
+            })
+            .map(|(libraries, _)| libraries)
+            .unwrap_or_default()
+    }
+
+    fn set_sort(&mut self, sort: AppSort) {
+        self.app_sort = sort;
+        let mut config = config::load();
+        config.app_sort = Some(self.app_sort.clone());
+        config::store(config);
+    }
+
+    /// Every action for the command palette.
+    fn commands(&self) -> Vec<Action> {
+        let mut commands = vec![
+            Action::OpenFile,
+            Action::Refresh,
+            Action::Save,
+            Action::Clear,
+            Action::Restore,
+            Action::FocusFilter,
+            Action::Accounts,
+            Action::Team,
+            Action::OrphanedPrefixes,
+            Action::ShaderCaches,
+            Action::ImportProtonDb,
+        ];
+        if !self.selection.is_empty() {
+            commands.push(Action::Gamescope);
+        }
+        commands.extend(
+            self.profiles
+                .names()
+                .iter()
+                .filter(|name| **name != self.profile)
+                .map(|name| Action::Profile(name.clone())),
+        );
+        commands.extend(self.saved_filters.keys().cloned().map(Action::Filter));
+        commands.extend(
+            [
+                AppSort::IdAscending,
+                AppSort::IdDescending,
+                AppSort::NameAscending,
+                AppSort::NameDescending,
+                AppSort::Relevance,
+                AppSort::ShaderCacheDescending,
+                AppSort::TierDescending,
+            ]
+            .into_iter()
+            .map(Action::Sort),
+        );
+        commands
+    }
+
+    fn run(&mut self, ctx: &egui::Context, action: Action) {
+        debug!("action: {:?}", action);
+        match action {
+            Action::OpenFile => self.open_file(),
+            Action::Refresh => {
+                if self.refresh() {
+                    ctx.memory_mut(|mem| mem.open_popup(egui::Id::new("refresh")));
+                }
+            }
+            Action::Save => {
+                if self.save() {
+                    ctx.memory_mut(|mem| mem.open_popup(egui::Id::new("save")));
+                }
+            }
+            Action::Clear => self.clear(),
+            Action::Restore => self.restore(),
+            Action::FocusFilter => self.focus_filter = true,
+            Action::Accounts => {
+                if let Some(localconfig_vdf_path) = &self.steam_config {
+                    self.accounts.load(localconfig_vdf_path);
+                }
+            }
+            Action::Team => self.team.load(),
+            Action::Gamescope => {
+                // Locked apps keep their launch options
+                let apps: Vec<(u32, String)> = self
+                    .selection
+                    .iter()
+                    .filter(|appid| !self.locks.get(appid).copied().unwrap_or_default())
+                    .map(|appid| {
+                        let launch_options = self.all_launch_options.get(appid).cloned();
+                        (*appid, launch_options.unwrap_or_default())
+                    })
+                    .collect();
+                if !apps.is_empty() {
+                    self.gamescope
+                        .load(apps, format!("{} selected apps", self.selection.len()));
+                }
+            }
+            Action::OrphanedPrefixes => {
+                if let Some(localconfig_vdf_path) = &self.steam_config {
+                    // Every app the libraries list, also those whose manifest could not be read,
+                    // e.g. while they are being downloaded
+                    let (libraries, appids) =
+                        libraryfolders_vdf::read_installed_apps(config_dir(localconfig_vdf_path))
+                            .unwrap_or_default();
+                    let installed = libraries
+                        .iter()
+                        .zip(appids.iter())
+                        .flat_map(|(library, appids)| {
+                            appids
+                                .iter()
+                                .filter_map(|appid| Some((appid.parse().ok()?, library.clone())))
+                        })
+                        .collect();
+                    self.compatdata.load_orphans(ctx, libraries, installed);
+                }
+            }
+            Action::ShaderCaches => self.shader_cache.open = true,
+            Action::ImportProtonDb => self.protondb_import.start(ctx),
+            Action::Palette => self.palette.load(),
+            Action::Profile(name) => self.switch_profile(name),
+            Action::Filter(name) => {
+                if let Some(filter) = self.saved_filters.get(&name) {
+                    self.filter_apps = filter.clone();
+                }
+            }
+            Action::Sort(sort) => self.set_sort(sort),
+        }
+    }
+
+    /// Moves the selected row with the keyboard and starts or cancels editing its launch
+    /// options. Returns the row to scroll to and whether any launch options or locks changed.
+    fn navigate(&mut self, ctx: &egui::Context, rows: &[u32]) -> (Option<usize>, bool) {
+        if self.palette.open || rows.is_empty() {
+            return (None, false);
+        }
+        let index = self
+            .selected
+            .and_then(|appid| rows.iter().position(|row| *row == appid));
+        let last = rows.len() - 1;
+        let mut scroll_to = None;
+        let mut changed = false;
+        if let Some((appid, original)) = self.editing.clone() {
+            let completions = self
+                .all_launch_options
+                .get(&appid)
+                .zip(self.completion_words.as_ref())
+                .and_then(|(launch_options, (_, words))| {
+                    ui::completion::completions(ctx, editor_id(appid), launch_options, words)
+                });
+            // The editor keeps the focus on Tab, so it can be moved to the next row here
+            let (escape, previous, next, up, down, enter) = ctx.input_mut(|i| {
+                let open = completions.is_some();
+                (
+                    i.key_pressed(egui::Key::Escape),
+                    i.consume_key(egui::Modifiers::SHIFT, egui::Key::Tab),
+                    i.consume_key(egui::Modifiers::NONE, egui::Key::Tab),
+                    open && i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                    open && i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                    open && i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                )
+            });
+            if escape {
+                self.all_launch_options.insert(appid, original);
+                self.editing = None;
+                changed = true;
+            } else if ctx.memory(|mem| mem.focused()) != Some(editor_id(appid)) {
+                // The editor was scrolled away or another widget took the focus
+                self.editing = None;
+            } else if let Some(completions) = &completions {
+                // Tab completes the word before it moves to the next row
+                let last = completions.items.len() - 1;
+                if (next || enter)
+                    && let Some(launch_options) = self.all_launch_options.get_mut(&appid)
+                {
+                    let selected = self.completion.min(last);
+                    ui::completion::accept(
+                        ctx,
+                        editor_id(appid),
+                        launch_options,
+                        completions,
+                        selected,
+                    );
+                    self.completion = 0;
+                    changed = true;
+                } else if up {
+                    self.completion = self.completion.min(last).saturating_sub(1);
+                } else if down {
+                    self.completion = (self.completion + 1).min(last);
+                } else if previous {
+                    scroll_to = index.map(|i| i.saturating_sub(1));
+                }
+            } else if previous {
+                scroll_to = index.map(|i| i.saturating_sub(1));
+            } else if next {
+                scroll_to = index.map(|i| (i + 1).min(last));
+            }
+            if let Some(i) = scroll_to {
+                self.selected = Some(rows[i]);
+                ctx.memory_mut(|mem| mem.request_focus(editor_id(rows[i])));
+            }
+        } else if ctx.memory(|mem| mem.focused()).is_none() {
+            let (up, down, home, end, enter, space) = ctx.input_mut(|i| {
+                (
+                    i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                    i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                    i.consume_key(egui::Modifiers::NONE, egui::Key::Home),
+                    i.consume_key(egui::Modifiers::NONE, egui::Key::End),
+                    i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                    i.consume_key(egui::Modifiers::NONE, egui::Key::Space),
+                )
+            });
+            scroll_to = if up {
+                Some(index.map_or(0, |i| i.saturating_sub(1)))
+            } else if down {
+                Some(index.map_or(0, |i| (i + 1).min(last)))
+            } else if home {
+                Some(0)
+            } else if end {
+                Some(last)
+            } else if enter || space {
+                index
+            } else {
+                None
+            };
+            if let Some(i) = scroll_to {
+                let appid = rows[i];
+                self.selected = Some(appid);
+                if enter {
+                    ctx.memory_mut(|mem| mem.request_focus(editor_id(appid)));
+                }
+                if space && let Some(lock) = self.locks.get_mut(&appid) {
+                    *lock = !*lock;
+                    changed = true;
+                }
+            }
+        }
+        (scroll_to, changed)
+    }
+
+    fn build_view(&mut self) -> AppView {
+        let Some(apps) = &self.apps else {
+            return AppView::default();
+        };
+        for (appid, properties) in apps.iter() {
+            self.locks.entry(*appid).or_insert(properties.lock);
+            self.all_launch_options
+                .entry(*appid)
+                .or_insert_with(|| properties.launch_options.clone());
+        }
+        let mut view = filter_apps(
+            &self.filter_apps,
+            self.app_sort.clone(),
+            apps,
+            &self.all_launch_options,
+            &AppLookups {
+                locks: &self.locks,
+                notes: &self.notes,
+                tags: &self.tags,
+                protondb_reports: &self.protondb_reports,
+            },
+        );
+        if self.app_sort == AppSort::ShaderCacheDescending {
+            view.rows.sort_by_key(|appid| {
+                std::cmp::Reverse(self.shader_cache_sizes.get(appid).copied().unwrap_or(0))
+            });
+        }
+        if self.app_sort == AppSort::TierDescending {
+            view.rows.sort_by_key(|appid| {
+                std::cmp::Reverse(self.protondb_reports.get(appid).map(|reports| reports.tier))
+            });
+        }
+        view
     }
 }
 
 impl eframe::App for EguiApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.warnings.append(&mut schema::take_warnings());
+
+        let shortcut = ctx.input_mut(|i| {
+            SHORTCUTS
+                .iter()
+                .find(|(shortcut, _)| i.consume_shortcut(shortcut))
+                .map(|(_, action)| action.clone())
+        });
+        if let Some(action) = shortcut {
+            self.run(ctx, action);
+        }
+
+        if self.apps.is_some() && self.shader_cache.needs_scan() {
+            self.shader_cache.scan(ctx, self.libraries());
+        }
+        if let Some(apps) = &self.apps
+            && let Some(sizes) = self.shader_cache.poll(apps)
+        {
+            self.shader_cache_sizes = sizes;
+            self.view = None;
+        }
+        match self.protondb_import.poll() {
+            Some(Ok(reports)) => {
+                self.protondb_reports = reports;
+                self.view = None;
+            }
+            Some(Err(err)) => self.warnings.push(err),
+            None => {}
+        }
+
+        if let Some(appid) = self.selected
+            && let (Some(localconfig_vdf_path), Some(apps)) = (&self.steam_config, &self.apps)
+        {
+            let launch_options = self
+                .all_launch_options
+                .get(&appid)
+                .map_or("", |launch_options| launch_options.as_str());
+            if !self.preview.show(
+                ctx,
+                appid,
+                apps,
+                launch_options,
+                &steam_dir(localconfig_vdf_path),
+            ) {
+                self.selected = None;
+            }
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
+            let mut dismissed = None;
+            for (i, warning) in self.warnings.iter().enumerate() {
+                ui.horizontal_wrapped(|ui| {
+                    if ui.small_button("✖").clicked() {
+                        dismissed = Some(i);
+                    }
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", warning));
+                });
+            }
+            if let Some(i) = dismissed {
+                self.warnings.remove(i);
+            }
+
             ui.horizontal_wrapped(|ui| {
                 ui.label("Find file:");
                 ui.monospace("Steam/userdata/XXXXXXXX/config/localconfig.vdf");
@@ -263,36 +1316,15 @@ impl eframe::App for EguiApp {
 
             ui.horizontal_wrapped(|ui| {
                 if ui.button("🗁 Open file…").clicked() {
-                    if let Some(path) = rfd::FileDialog::new()
-                        .add_filter("text", &["vdf"])
-                        .set_directory(userdata_dir())
-                        .pick_file()
-                    {
-                        self.steam_config = Some(path.to_str().unwrap_or_default().to_owned());
-                        if let Some(localconfig_vdf_path) = &self.steam_config {
-                            let mut config: Config =
-                                confy::load(consts::CODE_NAME, None).unwrap_or_default();
-                            config.steam_config = Some(localconfig_vdf_path.clone());
-                            confy::store(consts::CODE_NAME, None, config).unwrap_or_default();
-                            self.apps = update_apps(localconfig_vdf_path);
-                        }
-                    }
+                    self.open_file();
                 }
 
-                let response = ui.button("⟳ Refresh");
-                let popup_id = ui.make_persistent_id("refresh");
-                if response.clicked() {
-                    if let Some(localconfig_vdf_path) = &self.steam_config {
-                        let mut config: Config =
-                            confy::load(consts::CODE_NAME, None).unwrap_or_default();
-                        config.steam_config = Some(localconfig_vdf_path.clone());
-                        confy::store(consts::CODE_NAME, None, config).unwrap_or_default();
-                        self.apps = update_apps(localconfig_vdf_path);
-                        if let Some(apps) = &self.apps {
-                            update_launch_options(apps, &mut self.all_launch_options)
-                        }
-                        ui.memory_mut(|mem| mem.open_popup(popup_id));
-                    }
+                let response = ui
+                    .button("⟳ Refresh")
+                    .on_hover_text(Action::Refresh.shortcut(ui.ctx()));
+                let popup_id = egui::Id::new("refresh");
+                if response.clicked() && self.refresh() {
+                    ui.memory_mut(|mem| mem.open_popup(popup_id));
                 }
                 egui::popup_above_or_below_widget(
                     ui,
@@ -306,56 +1338,64 @@ impl eframe::App for EguiApp {
                     },
                 );
 
+                if self.steam_config.is_some() && ui.button("👥 Accounts").clicked() {
+                    self.run(ui.ctx(), Action::Accounts);
+                }
+
+                if self.steam_config.is_some() && ui.button("🤝 Team").clicked() {
+                    self.run(ui.ctx(), Action::Team);
+                }
+
+                if self.steam_config.is_some()
+                    && ui
+                        .button("🍷 Prefixes")
+                        .on_hover_text("Proton prefixes of apps that are no longer installed")
+                        .clicked()
+                {
+                    self.run(ui.ctx(), Action::OrphanedPrefixes);
+                }
+
+                if self.steam_config.is_some()
+                    && ui
+                        .button("💾 Shader caches")
+                        .on_hover_text("Shader caches per library, including uninstalled apps")
+                        .clicked()
+                {
+                    self.run(ui.ctx(), Action::ShaderCaches);
+                }
+
+                if ui
+                    .button("⌨")
+                    .on_hover_text(format!(
+                        "Command palette ({})",
+                        Action::Palette.shortcut(ui.ctx())
+                    ))
+                    .clicked()
+                {
+                    self.run(ui.ctx(), Action::Palette);
+                }
+
                 if let Some(picked_path) = &self.steam_config {
                     ui.label("Picked file:");
                     ui.monospace(picked_path);
                 }
             });
 
-            if let Some(picked_path) = &self.steam_config {
+            if self.steam_config.is_some() {
                 ui.separator();
 
                 ui.horizontal_wrapped(|ui| {
-                    let response = ui.button("💾 Save");
-                    let popup_id = ui.make_persistent_id("save");
-                    if response.clicked() {
-                        let mut config: Config =
-                            confy::load(consts::CODE_NAME, None).unwrap_or_default();
-                        let previous_default_launch_options =
-                            config.default_launch_options.unwrap_or_default();
-                        config.default_launch_options = Some(self.default_launch_options.clone());
-                        confy::store(consts::CODE_NAME, None, config).unwrap_or_default();
-
-                        let mut profile: Profile = Profile::default();
-                        let mut locks: Vec<String> = Vec::new();
-                        for (key, value) in self.locks.iter() {
-                            if *value {
-                                locks.push(key.to_string());
-                            }
-                        }
-                        profile.locks = Some(locks.clone());
-                        if !self.default_launch_options.trim().is_empty() {
-                            for (appid, launch_options) in self.all_launch_options.iter_mut() {
-                                if (launch_options.is_empty()
-                                    && !locks.contains(&appid.clone().to_string()))
-                                    || launch_options == &previous_default_launch_options
-                                {
-                                    *launch_options = self.default_launch_options.clone();
-                                }
-                            }
-                        }
-                        let mut options: BTreeMap<String, String> = BTreeMap::new();
-                        for (key, value) in self.all_launch_options.iter() {
-                            options.insert(key.to_string(), value.clone());
-                        }
-                        profile.options = Some(options);
-                        confy::store(consts::CODE_NAME, "profile", profile).unwrap();
-                        backup_file(picked_path, ".bak").expect("Error backup failed");
-                        localconfig_vdf::write_launch_options(
-                            picked_path,
-                            &self.all_launch_options,
-                        )
-                        .expect("Error failed to write launch options to config");
+                    if let Some(name) = self.profiles.show(ui, &mut self.profile) {
+                        self.switch_profile(name);
+                    }
+                });
+
+                ui.horizontal_wrapped(|ui| {
+                    let response = ui
+                        .button("💾 Save")
+                        .on_hover_text(Action::Save.shortcut(ui.ctx()));
+                    let popup_id = egui::Id::new("save");
+                    if response.clicked() && self.save() {
                         ui.memory_mut(|mem| mem.open_popup(popup_id));
                     };
                     egui::popup_above_or_below_widget(
@@ -370,24 +1410,25 @@ impl eframe::App for EguiApp {
                         },
                     );
 
+                    let dirty = self.dirty_count();
+                    if dirty > 0 {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("✏ {} unsaved", dirty),
+                        )
+                        .on_hover_text("Apps whose launch options differ from localconfig.vdf");
+                    }
+
                     if ui.button("🗑 Clear").clicked() {
-                        for launch_options in self.all_launch_options.values_mut() {
-                            launch_options.clear();
-                        }
+                        self.clear();
                     }
 
-                    if ui.button("🔄 Restore").clicked() {
-                        let profile: Profile =
-                            confy::load(consts::CODE_NAME, "profile").unwrap_or_default();
-                        if let Some(options) = &profile.options {
-                            let mut profile: BTreeMap<u32, String> = BTreeMap::new();
-                            for (key, value) in options.iter() {
-                                profile.insert(key.parse::<u32>().unwrap(), value.clone());
-                            }
-                            self.all_launch_options = profile.clone();
-                        } else if let Some(apps) = &self.apps {
-                            update_launch_options(apps, &mut self.all_launch_options)
-                        }
+                    if ui
+                        .button("🔄 Restore")
+                        .on_hover_text(Action::Restore.shortcut(ui.ctx()))
+                        .clicked()
+                    {
+                        self.restore();
                     }
                     ui.label("Set default launch options:");
                     ui.add_sized(
@@ -424,14 +1465,25 @@ impl eframe::App for EguiApp {
                                 AppSort::NameDescending,
                                 AppSort::NameDescending.to_string(),
                             );
+                            ui.selectable_value(
+                                &mut selected,
+                                AppSort::Relevance,
+                                AppSort::Relevance.to_string(),
+                            );
+                            ui.selectable_value(
+                                &mut selected,
+                                AppSort::ShaderCacheDescending,
+                                AppSort::ShaderCacheDescending.to_string(),
+                            );
+                            ui.selectable_value(
+                                &mut selected,
+                                AppSort::TierDescending,
+                                AppSort::TierDescending.to_string(),
+                            );
                         });
 
                     if selected != before {
-                        self.app_sort = selected;
-                        let mut config: Config =
-                            confy::load(consts::CODE_NAME, None).unwrap_or_default();
-                        config.app_sort = Some(self.app_sort.to_string());
-                        confy::store(consts::CODE_NAME, None, config).unwrap_or_default();
+                        self.set_sort(selected);
                     }
 
                     if cfg!(unix) {
@@ -446,118 +1498,784 @@ impl eframe::App for EguiApp {
                                 self.url = "https://store.steampowered.com/app/".to_string();
                             }
                             self.protondb = selected;
-                            let mut config: Config =
-                                confy::load(consts::CODE_NAME, None).unwrap_or_default();
+                            let mut config = config::load();
                             config.protondb = Some(self.protondb);
-                            confy::store(consts::CODE_NAME, None, config).unwrap_or_default();
+                            config::store(config);
                         }
                     }
 
+                    if ui
+                        .add_enabled(
+                            !self.protondb_import.is_loading(),
+                            egui::Button::new("⚛ Import…"),
+                        )
+                        .on_hover_text(format!(
+                            "Import a ProtonDB report dump for tiers and launch option \
+                             suggestions, {} apps imported",
+                            self.protondb_reports.len()
+                        ))
+                        .clicked()
+                    {
+                        self.run(ui.ctx(), Action::ImportProtonDb);
+                    }
+
+                    if ui
+                        .checkbox(&mut self.git_history, "🕘 Git history")
+                        .on_hover_text("Keep every saved profile in a local git repository")
+                        .changed()
+                    {
+                        let mut config = config::load();
+                        config.git_history = Some(self.git_history);
+                        config::store(config);
+                    }
+
+                    ui.menu_button("⭐", |ui| {
+                        let mut changed = false;
+                        let mut removed = None;
+                        for (name, filter) in self.saved_filters.iter() {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("🗑").clicked() {
+                                    removed = Some(name.clone());
+                                }
+                                if ui.button(name).on_hover_text(filter).clicked() {
+                                    self.filter_apps = filter.clone();
+                                    ui.close_menu();
+                                }
+                            });
+                        }
+                        if let Some(name) = removed {
+                            self.saved_filters.remove(&name);
+                            changed = true;
+                        }
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            ui.text_edit_singleline(&mut self.saved_filter_name);
+                            let name = self.saved_filter_name.trim().to_string();
+                            if ui
+                                .add_enabled(
+                                    !name.is_empty() && !self.filter_apps.trim().is_empty(),
+                                    egui::Button::new("💾 Save filter"),
+                                )
+                                .clicked()
+                            {
+                                self.saved_filters.insert(name, self.filter_apps.clone());
+                                self.saved_filter_name.clear();
+                                changed = true;
+                            }
+                        });
+                        if changed {
+                            let mut config = config::load();
+                            config.saved_filters = Some(self.saved_filters.clone());
+                            config::store(config);
+                        }
+                    })
+                    .response
+                    .on_hover_text("Saved filters");
+
                     ui.label("Filter apps:");
-                    ui.add_sized(
+                    let response = ui.add_sized(
                         ui.available_size_before_wrap(),
-                        egui::TextEdit::singleline(&mut self.filter_apps),
-                    );
+                        egui::TextEdit::singleline(&mut self.filter_apps).hint_text(
+                            "name, id:570, has:options, locked:true, env:PROTON_LOG, tag:…, AND/OR/NOT",
+                        ),
+                    )
+                    .on_hover_text(FILTER_HELP);
+                    if std::mem::take(&mut self.focus_filter) {
+                        response.request_focus();
+                    }
                 });
 
+                let view = match self.view.take() {
+                    Some(view)
+                        if view.filter == self.filter_apps && view.sort == self.app_sort =>
+                    {
+                        view
+                    }
+                    _ => self.build_view(),
+                };
+                if let Some(err) = &view.error {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ Filter: {}", err));
+                }
+                if !self.selection.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} apps selected", self.selection.len()));
+                        if ui.button("🖥 Gamescope").clicked() {
+                            self.run(ui.ctx(), Action::Gamescope);
+                        }
+                        if ui.button("✖ Clear selection").clicked() {
+                            self.selection.clear();
+                        }
+                    });
+                }
+
                 ui.separator();
 
-                TableBuilder::new(ui)
+                match self.editing.as_ref().map(|(appid, _)| *appid) {
+                    Some(appid)
+                        if self
+                            .completion_words
+                            .as_ref()
+                            .is_none_or(|(words_appid, _)| *words_appid != appid) =>
+                    {
+                        self.completion_words = Some((appid, self.completion_words(appid)));
+                    }
+                    Some(_) => {}
+                    None => self.completion_words = None,
+                }
+                let (scroll_to, mut changed) = self.navigate(ui.ctx(), &view.rows);
+                let mut table = TableBuilder::new(ui)
                     .resizable(true)
+                    .striped(true)
+                    .sense(egui::Sense::click());
+                if let Some(row) = scroll_to {
+                    table = table.scroll_to_row(row, None);
+                }
+                table
                     .column(Column::auto().at_least(5.0))
-                    .column(Column::auto().at_least(150.0))
+                    .column(Column::auto().at_least(150.0).clip(true))
+                    .column(Column::auto().at_least(80.0).clip(true))
+                    .column(Column::auto().at_least(60.0))
+                    .column(Column::auto().at_least(60.0))
                     .column(Column::remainder())
                     .header(20.0, |mut header| {
                         header.col(|ui| {
                             ui.heading("🔒 Lock");
                         });
                         header.col(|ui| {
-                            ui.heading(" Steam Apps");
+                            ui.heading(" Steam Apps")
+                                .on_hover_text("Ctrl+click rows to select apps for bulk actions");
+                        });
+                        header.col(|ui| {
+                            ui.heading("🏷 Tags");
+                        });
+                        header.col(|ui| {
+                            ui.heading("💾 Shader cache");
+                        });
+                        header.col(|ui| {
+                            ui.heading("⚛ Tier");
                         });
                         header.col(|ui| {
                             ui.heading("⚙ Launch Options");
                         });
                     })
-                    .body(|mut body| {
-                        body.row(0.0, |mut row| {
+                    .body(|body| {
+                        let Some(apps) = &self.apps else {
+                            return;
+                        };
+                        body.rows(24.0, view.rows.len(), |mut row| {
+                            let appid = &view.rows[row.index()];
+                            let Some(properties) = apps.get(appid) else {
+                                return;
+                            };
+                            row.set_selected(
+                                self.selected == Some(*appid) || self.selection.contains(appid),
+                            );
+                            row.col(|ui| {
+                                if let Some(lock) = self.locks.get_mut(appid)
+                                    && ui.add(egui::Checkbox::without_text(lock)).changed()
+                                {
+                                    changed = true;
+                                }
+                            });
                             row.col(|ui| {
-                                if let Some(apps) = &self.apps {
-                                    let sorted_apps = sort_apps(self.app_sort.clone(), apps);
-                                    for (appid, properties) in sorted_apps.into_iter() {
-                                        let mut current_locks = properties.lock.clone();
-                                        match self.locks.get(&appid) {
-                                            Some(locks) => current_locks = locks.clone(),
-                                            None => {
-                                                self.locks.insert(*appid, current_locks.clone());
+                                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
+                                let mut response = ui.add(egui::Hyperlink::from_label_and_url(
+                                    highlight(ui, &properties.name, &view.words),
+                                    self.url.clone() + &appid.to_string(),
+                                ));
+                                if let Some(note) = self.notes.get(appid) {
+                                    response = response.on_hover_text(note);
+                                }
+                                response.context_menu(|ui| {
+                                    if ui.button("📝 Notes").clicked() {
+                                        self.notes_window.load(
+                                            *appid,
+                                            &properties.name,
+                                            self.notes.get(appid),
+                                            self.tags.get(appid),
+                                        );
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("🖥 Gamescope").clicked() {
+                                        self.gamescope.load(
+                                            vec![(
+                                                *appid,
+                                                self.all_launch_options
+                                                    .get(appid)
+                                                    .cloned()
+                                                    .unwrap_or_default(),
+                                            )],
+                                            properties.name.clone(),
+                                        );
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("🍷 Prefix").clicked() {
+                                        self.compatdata.load_app(
+                                            ui.ctx(),
+                                            *appid,
+                                            &properties.name,
+                                            properties.library.clone(),
+                                        );
+                                        ui.close_menu();
+                                    }
+                                    if let Some(localconfig_vdf_path) = &self.steam_config
+                                        && ui.button("📊 MangoHud").clicked()
+                                    {
+                                        self.mangohud.load(
+                                            *appid,
+                                            &properties.name,
+                                            self.all_launch_options
+                                                .get(appid)
+                                                .map_or("", |launch_options| {
+                                                    launch_options.as_str()
+                                                }),
+                                            &steam_dir(localconfig_vdf_path),
+                                        );
+                                        ui.close_menu();
+                                    }
+                                    if let Some(localconfig_vdf_path) = &self.steam_config
+                                        && ui.button("🧩 DXVK and VKD3D").clicked()
+                                    {
+                                        self.dxvk.load(
+                                            *appid,
+                                            &properties.name,
+                                            self.all_launch_options
+                                                .get(appid)
+                                                .map_or("", |launch_options| {
+                                                    launch_options.as_str()
+                                                }),
+                                            &properties
+                                                .library
+                                                .join("steamapps/common")
+                                                .join(&properties.installdir),
+                                            &steam_dir(localconfig_vdf_path),
+                                        );
+                                        ui.close_menu();
+                                    }
+                                    if let Some(localconfig_vdf_path) = &self.steam_config
+                                        && ui.button("🧾 Logs").clicked()
+                                    {
+                                        self.logs.load(
+                                            *appid,
+                                            &properties.name,
+                                            self.all_launch_options
+                                                .get(appid)
+                                                .map_or("", |launch_options| {
+                                                    launch_options.as_str()
+                                                }),
+                                            self.hooks.get(appid),
+                                            &steam_dir(localconfig_vdf_path),
+                                        );
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("🔗 Hooks").clicked() {
+                                        self.hooks_window.load(
+                                            *appid,
+                                            &properties.name,
+                                            self.hooks.get(appid),
+                                        );
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("📜 Wrapper script").clicked() {
+                                        self.wrapper.load(
+                                            *appid,
+                                            &properties.name,
+                                            self.all_launch_options
+                                                .get(appid)
+                                                .map_or("", |launch_options| {
+                                                    launch_options.as_str()
+                                                }),
+                                            self.hooks.get(appid),
+                                        );
+                                        ui.close_menu();
+                                    }
+                                    if let Some(reports) = self.protondb_reports.get(appid)
+                                        && !reports.suggestions.is_empty()
+                                    {
+                                        ui.menu_button("⚛ ProtonDB launch options", |ui| {
+                                            for suggestion in reports.suggestions.iter() {
+                                                if ui
+                                                    .button(&suggestion.launch_options)
+                                                    .on_hover_text(format!(
+                                                        "{} reports, added to the current \
+                                                         launch options",
+                                                        suggestion.reports
+                                                    ))
+                                                    .clicked()
+                                                {
+                                                    let mut merged = launch_options::parse(
+                                                        self.all_launch_options
+                                                            .get(appid)
+                                                            .map_or("", |text| text.as_str()),
+                                                    );
+                                                    merged.merge(&launch_options::parse(
+                                                        &suggestion.launch_options,
+                                                    ));
+                                                    self.all_launch_options
+                                                        .insert(*appid, merged.to_string());
+                                                    self.pending_sources.insert(
+                                                        *appid,
+                                                        app_history::Source::Import,
+                                                    );
+                                                    changed = true;
+                                                    ui.close_menu();
+                                                }
                                             }
-                                        }
-
-                                        if is_filtered(&self.filter_apps, &properties.name) {
-                                            ui.style_mut().wrap_mode =
-                                                Some(egui::TextWrapMode::Truncate);
-                                            ui.add_sized(
-                                                [ui.available_width(), 20.0],
-                                                egui::Checkbox::without_text(&mut current_locks),
-                                            );
-                                            self.locks.insert(*appid, current_locks);
-                                        }
+                                        });
+                                    }
+                                    if ui.button("🕓 Timeline").clicked() {
+                                        self.app_history.load(
+                                            self.steam_config.as_deref().unwrap_or_default(),
+                                            *appid,
+                                            &properties.name,
+                                        );
+                                        ui.close_menu();
+                                    }
+                                    if ui
+                                        .add_enabled(
+                                            self.git_history,
+                                            egui::Button::new("🕘 History"),
+                                        )
+                                        .clicked()
+                                    {
+                                        self.profile_history.load(
+                                            &self.profile,
+                                            *appid,
+                                            &properties.name,
+                                        );
+                                        ui.close_menu();
                                     }
+                                });
+                            });
+                            row.col(|ui| {
+                                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
+                                let tags = self.tags.get(appid);
+                                let note = self.notes.get(appid);
+                                let mut text = tags
+                                    .map(|tags| {
+                                        tags.iter()
+                                            .map(|tag| format!("#{}", tag))
+                                            .collect::<Vec<String>>()
+                                            .join(" ")
+                                    })
+                                    .unwrap_or_default();
+                                if note.is_some() {
+                                    text = format!("📝 {}", text);
+                                }
+                                let mut response = ui.add_sized(
+                                    [ui.available_width(), 20.0],
+                                    egui::Button::new(text).frame(false),
+                                );
+                                if let Some(note) = note {
+                                    response = response.on_hover_text(note);
+                                }
+                                if response.clicked() {
+                                    self.notes_window.load(*appid, &properties.name, note, tags);
                                 }
                             });
                             row.col(|ui| {
-                                if let Some(apps) = &self.apps {
-                                    let sorted_apps = sort_apps(self.app_sort.clone(), apps);
-                                    for (appid, properties) in sorted_apps.into_iter() {
-                                        if is_filtered(&self.filter_apps, &properties.name) {
-                                            ui.style_mut().wrap_mode =
-                                                Some(egui::TextWrapMode::Truncate);
-                                            ui.add_sized(
-                                                [ui.available_width(), 20.0],
-                                                egui::Hyperlink::from_label_and_url(
-                                                    &properties.name,
-                                                    self.url.clone() + &appid.to_string(),
-                                                ),
-                                            );
-                                        }
-                                    }
+                                if let Some(size) = self.shader_cache_sizes.get(appid) {
+                                    ui.label(fsutil::format_size(*size));
                                 }
                             });
                             row.col(|ui| {
-                                if let Some(apps) = &self.apps {
-                                    let sorted_apps = sort_apps(self.app_sort.clone(), apps);
-                                    for (appid, properties) in sorted_apps.into_iter() {
-                                        let mut current_launch_options =
-                                            properties.launch_options.clone();
-                                        match self.all_launch_options.get(&appid) {
-                                            Some(launch_options) => {
-                                                current_launch_options = launch_options.clone()
-                                            }
-                                            None => {
-                                                self.all_launch_options
-                                                    .insert(*appid, current_launch_options.clone());
-                                            }
-                                        }
-
-                                        if is_filtered(&self.filter_apps, &properties.name) {
-                                            ui.style_mut().wrap_mode =
-                                                Some(egui::TextWrapMode::Truncate);
-                                            let response = ui.add_sized(
-                                                [ui.available_width() - 20.0, 20.0],
-                                                egui::TextEdit::singleline(
-                                                    &mut current_launch_options,
-                                                ),
-                                            );
-                                            if response.changed() {
-                                                self.all_launch_options
-                                                    .insert(*appid, current_launch_options);
-                                            }
-                                        }
-                                    }
+                                if let Some(reports) = self.protondb_reports.get(appid) {
+                                    ui.label(ui::protondb::tier_text(reports.tier))
+                                        .on_hover_text(format!("{} reports", reports.reports));
+                                }
+                            });
+                            row.col(|ui| {
+                                let Some(launch_options) = self.all_launch_options.get_mut(appid)
+                                else {
+                                    return;
+                                };
+                                let problems = env_catalog::lint(launch_options);
+                                let icons = if problems.is_empty() { 1.0 } else { 2.0 };
+                                let mut response = ui.add_sized(
+                                    [ui.available_width() - 20.0 * icons, 20.0],
+                                    egui::TextEdit::singleline(launch_options)
+                                        .id(editor_id(*appid))
+                                        .lock_focus(true),
+                                );
+                                if let Some(help) = ui::completion::env_help(launch_options) {
+                                    response = response.on_hover_text(help);
+                                }
+                                if response.changed() {
+                                    self.completion = 0;
+                                    changed = true;
+                                }
+                                if response.has_focus()
+                                    && let Some((_, words)) = &self.completion_words
+                                    && let Some(completions) = ui::completion::completions(
+                                        ui.ctx(),
+                                        editor_id(*appid),
+                                        launch_options,
+                                        words,
+                                    )
+                                    && let Some(i) = ui::completion::show(
+                                        ui.ctx(),
+                                        &response,
+                                        &completions,
+                                        self.completion.min(completions.items.len() - 1),
+                                    )
+                                {
+                                    ui::completion::accept(
+                                        ui.ctx(),
+                                        editor_id(*appid),
+                                        launch_options,
+                                        &completions,
+                                        i,
+                                    );
+                                    response.request_focus();
+                                    changed = true;
+                                }
+                                if !problems.is_empty() {
+                                    ui.colored_label(ui.visuals().warn_fg_color, "⚠")
+                                        .on_hover_text(problems.join("\n"));
+                                }
+                                if response.gained_focus() {
+                                    self.selected = Some(*appid);
+                                    self.editing = Some((*appid, launch_options.clone()));
+                                }
+                                if response.lost_focus()
+                                    && self
+                                        .editing
+                                        .as_ref()
+                                        .is_some_and(|(editing, _)| editing == appid)
+                                {
+                                    self.editing = None;
+                                }
+                                if properties.launch_options != *launch_options
+                                    && ui
+                                        .small_button("⟲")
+                                        .on_hover_text(format!(
+                                            "Unsaved, click to revert to: {}",
+                                            properties.launch_options
+                                        ))
+                                        .clicked()
+                                {
+                                    *launch_options = properties.launch_options.clone();
+                                    changed = true;
                                 }
                             });
+                            if row.response().clicked() {
+                                if ctx.input(|i| i.modifiers.command) {
+                                    if !self.selection.remove(appid) {
+                                        self.selection.insert(*appid);
+                                    }
+                                } else {
+                                    self.selected = Some(*appid);
+                                }
+                            }
                         });
                     });
+                if !changed {
+                    self.view = Some(view);
+                }
             }
         });
+
+        if self.accounts.open
+            && let (Some(localconfig_vdf_path), Some(apps)) = (&self.steam_config, &mut self.apps)
+        {
+            let copied = self.accounts.show(ctx, apps, localconfig_vdf_path);
+            if !copied.is_empty() {
+                self.view = None;
+                if let Err(err) = app_history::record_all(
+                    localconfig_vdf_path,
+                    copied.iter().map(|(appid, launch_options)| {
+                        (*appid, launch_options.as_str(), app_history::Source::Import)
+                    }),
+                ) {
+                    warn!("error recording app history: {}", err);
+                }
+            }
+            for (appid, launch_options) in copied.into_iter() {
+                if let Some(app) = apps.get_mut(&appid) {
+                    app.launch_options = launch_options.clone();
+                }
+                self.all_launch_options.insert(appid, launch_options);
+            }
+        }
+
+        if self.team.open
+            && let Some(apps) = &self.apps
+        {
+            let mut local = ui::team::Local {
+                all_launch_options: &mut self.all_launch_options,
+                notes: &mut self.notes,
+                tags: &mut self.tags,
+            };
+            let before = local.all_launch_options.clone();
+            if self.team.show(ctx, apps, &mut local) {
+                for (appid, launch_options) in self.all_launch_options.iter() {
+                    if before.get(appid) != Some(launch_options) {
+                        self.pending_sources
+                            .insert(*appid, app_history::Source::Import);
+                    }
+                }
+                self.view = None;
+            }
+        }
+
+        if self.notes_window.open
+            && let Some((appid, note, tags)) = self.notes_window.show(ctx)
+        {
+            if note.is_empty() {
+                self.notes.remove(&appid);
+            } else {
+                self.notes.insert(appid, note);
+            }
+            if tags.is_empty() {
+                self.tags.remove(&appid);
+            } else {
+                self.tags.insert(appid, tags);
+            }
+            self.view = None;
+        }
+
+        if self.hooks_window.open
+            && let Some((appid, hooks)) = self.hooks_window.show(ctx)
+        {
+            let script = if hooks.is_empty() {
+                self.hooks.remove(&appid);
+                None
+            } else {
+                let script = hooks::write(appid, &hooks)
+                    .inspect_err(|err| warn!("error writing hooks: {}", err))
+                    .ok();
+                self.hooks.insert(appid, hooks);
+                script
+            };
+            if let Some(launch_options) = self.all_launch_options.get_mut(&appid) {
+                if wrapper::is_wrapped(launch_options, appid) {
+                    // The wrapper script runs the hooks in place of the hooks script
+                    *launch_options = hooks::apply(launch_options, None);
+                    if let Err(err) = wrapper::regenerate(
+                        appid,
+                        self.hooks.get(&appid).unwrap_or(&hooks::Hooks::default()),
+                    ) {
+                        warn!("error regenerating wrapper script: {}", err);
+                    }
+                } else {
+                    *launch_options = hooks::apply(launch_options, script.as_deref());
+                }
+            }
+            self.view = None;
+        }
+
+        if self.gamescope.open
+            && let Some((appids, options)) = self.gamescope.show(ctx)
+        {
+            for appid in appids.iter() {
+                if let Some(launch_options) = self.all_launch_options.get_mut(appid) {
+                    *launch_options = gamescope::apply(launch_options, options.as_ref());
+                }
+            }
+            self.view = None;
+        }
+
+        if self.mangohud.open
+            && let Some((appid, path)) = self.mangohud.show(ctx)
+            && let Some(launch_options) = self.all_launch_options.get_mut(&appid)
+        {
+            *launch_options = mangohud::apply(launch_options, path.as_deref());
+            self.view = None;
+        }
+
+        if self.dxvk.open
+            && let Some((appid, path, vkd3d)) = self.dxvk.show(ctx)
+            && let Some(launch_options) = self.all_launch_options.get_mut(&appid)
+        {
+            *launch_options = dxvk::apply(launch_options, path.as_deref(), &vkd3d);
+            self.view = None;
+        }
+
+        if self.logs.open
+            && let Some(launch_options) = self.all_launch_options.get_mut(&self.logs.appid())
+            && let Some(text) = self.logs.show(ctx, launch_options)
+        {
+            *launch_options = text;
+            self.view = None;
+        }
+
+        if self.wrapper.open
+            && let Some((appid, mut launch_options)) = self.wrapper.show(ctx)
+        {
+            // Back to launch options, the hooks script runs the hooks again
+            if !wrapper::is_wrapped(&launch_options, appid) && self.hooks.contains_key(&appid) {
+                let script = hooks::script_path(appid).ok();
+                launch_options = hooks::apply(&launch_options, script.as_deref());
+            }
+            self.all_launch_options.insert(appid, launch_options);
+            self.view = None;
+        }
+
+        if self.compatdata.open {
+            self.compatdata.show(ctx);
+        }
+
+        // Also while closed, to notice a deletion that finished
+        if let Some(apps) = &self.apps
+            && self.shader_cache.show(ctx, apps, &self.selection)
+        {
+            self.shader_cache.scan(ctx, self.libraries());
+        }
+
+        self.confirm_unsaved(ctx);
+
+        if self.palette.open {
+            let commands = self.commands();
+            let labels: Vec<(String, String)> = commands
+                .iter()
+                .map(|action| (action.label(), action.shortcut(ctx)))
+                .collect();
+            if let Some(i) = self.palette.show(ctx, &labels) {
+                self.run(ctx, commands[i].clone());
+            }
+        }
+
+        if self.profile_history.open
+            && let Some((appid, launch_options)) = self.profile_history.show(ctx)
+        {
+            self.all_launch_options.insert(appid, launch_options);
+            self.pending_sources
+                .insert(appid, app_history::Source::Revert);
+            self.view = None;
+        }
+
+        if self.app_history.open {
+            let current = self
+                .all_launch_options
+                .get(&self.app_history.appid())
+                .cloned()
+                .unwrap_or_default();
+            if let Some((appid, launch_options)) = self.app_history.show(ctx, &current) {
+                self.all_launch_options.insert(appid, launch_options);
+                self.pending_sources
+                    .insert(appid, app_history::Source::Revert);
+                self.view = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    /// Synthetic library for measuring how the table scales with large libraries.
+    fn synthetic_apps(count: u32) -> BTreeMap<u32, App> {
+        let words = [
+            "Portal",
+            "Witcher",
+            "Souls",
+            "Dark",
+            "Half-Life",
+            "Pokémon",
+            "Hunt",
+        ];
+        (0..count)
+            .map(|i| {
+                let app = App {
+                    name: format!(
+                        "{} {} {}",
+                        words[i as usize % words.len()],
+                        words[i as usize / words.len() % words.len()],
+                        i
+                    ),
+                    launch_options: if i % 3 == 0 {
+                        "PROTON_LOG=1 %command%".to_string()
+                    } else {
+                        String::new()
+                    },
+                    lock: i % 10 == 0,
+                    library: PathBuf::from("/mnt/games"),
+                    installdir: format!("Game {}", i),
+                };
+                (i * 10, app)
+            })
+            .collect()
+    }
+
+    fn filter(filter: &str, sort: AppSort, apps: &BTreeMap<u32, App>) -> AppView {
+        filter_apps(
+            filter,
+            sort,
+            apps,
+            &BTreeMap::new(),
+            &AppLookups {
+                locks: &BTreeMap::new(),
+                notes: &BTreeMap::new(),
+                tags: &BTreeMap::new(),
+                protondb_reports: &BTreeMap::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_save_over_newer_profile() {
+        let name = format!("test-newer-{}", std::process::id());
+        let path = confy::get_configuration_file_path(
+            consts::CODE_NAME,
+            Some(profile::config_name(&name).as_str()),
+        )
+        .unwrap();
+        schema::leave_unchanged(path);
+        let mut app = EguiApp {
+            steam_config: Some("/nonexistent/localconfig.vdf".to_string()),
+            profile: name,
+            ..Default::default()
+        };
+        app.all_launch_options.insert(1111111, "-novid".to_string());
+        assert!(!app.save());
+        assert_eq!(app.all_launch_options[&1111111], "-novid");
+        assert!(
+            schema::take_warnings()
+                .iter()
+                .any(|warning| warning.contains("newer version"))
+        );
+    }
+
+    #[test]
+    fn test_filter_apps() {
+        let apps = synthetic_apps(49);
+        let view = filter("witcher souls", AppSort::IdAscending, &apps);
+        assert_eq!(view.rows, vec![90, 150]);
+        assert_eq!(view.words, vec!["witcher", "souls"]);
+
+        let view = filter("portal", AppSort::Relevance, &apps);
+        assert_eq!(view.rows.len(), 13);
+        assert!(apps[&view.rows[0]].name.starts_with("Portal"));
+
+        let view = filter("env:PROTON_LOG", AppSort::IdDescending, &apps);
+        assert_eq!(view.rows.len(), 17);
+        assert_eq!(view.rows[0], 480);
+
+        let view = filter("(portal", AppSort::IdAscending, &apps);
+        assert!(view.error.is_some());
+        assert_eq!(view.rows.len(), 49);
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with --ignored --nocapture"]
+    fn bench_filter_apps() {
+        let apps = synthetic_apps(10_000);
+        for (text, sort) in [
+            ("", AppSort::NameAscending),
+            ("witcher", AppSort::Relevance),
+            ("has:options NOT locked:true", AppSort::IdDescending),
+        ] {
+            let start = Instant::now();
+            let view = filter(text, sort, &apps);
+            println!(
+                "{:?}: {} of {} apps in {:?}",
+                text,
+                view.rows.len(),
+                apps.len(),
+                start.elapsed()
+            );
+        }
     }
 }
@@ -0,0 +1,232 @@
+//! Per-game MangoHud config files, passed to MangoHud with `MANGOHUD_CONFIGFILE`.
+
+use crate::config;
+use crate::launch_options;
+use directories::BaseDirs;
+use log::debug;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const COMMAND: &str = "mangohud";
+pub const CONFIG_FILE: &str = "MANGOHUD_CONFIGFILE";
+
+/// The lines of a config file, each `key=value` or a bare `key` that turns an option on.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MangoHudConfig {
+    pub entries: Vec<(String, Option<String>)>,
+}
+
+pub fn parse(text: &str) -> MangoHudConfig {
+    let entries = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.split_once('=') {
+            Some((key, value)) => (key.trim().to_string(), Some(value.trim().to_string())),
+            None => (line.to_string(), None),
+        })
+        .collect();
+    MangoHudConfig { entries }
+}
+
+impl MangoHudConfig {
+    pub fn is_set(&self, key: &str) -> bool {
+        self.entries.iter().any(|(name, _)| name == key)
+    }
+
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(name, _)| name == key)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// Replaces the entry in place or appends it.
+    pub fn set(&mut self, key: &str, value: Option<String>) {
+        match self.entries.iter_mut().find(|(name, _)| name == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key.to_string(), value)),
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.entries.retain(|(name, _)| name != key);
+    }
+}
+
+impl std::fmt::Display for MangoHudConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (key, value) in self.entries.iter() {
+            match value {
+                Some(value) => writeln!(f, "{}={}", key, value)?,
+                None => writeln!(f, "{}", key)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The config file written for the app.
+pub fn managed_path(appid: u32) -> Result<PathBuf, Box<dyn Error>> {
+    config::managed_file("mangohud", appid, "conf")
+}
+
+/// MangoHud's own config directory, `~/.config/MangoHud`.
+pub fn user_dir() -> Option<PathBuf> {
+    BaseDirs::new().map(|dirs| dirs.config_dir().join("MangoHud"))
+}
+
+/// Existing config files that MangoHud would read for the app: the one in its launch options,
+/// then the files named after its executables, `wine-` prefixed for Windows games.
+pub fn detect(user_dir: &Path, launch_options: &str, executables: &[String]) -> Vec<PathBuf> {
+    let mut candidates = vec![];
+    if let Some(path) = launch_options::parse(launch_options).env(CONFIG_FILE) {
+        candidates.push(PathBuf::from(path));
+    }
+    for executable in executables.iter() {
+        let Some(stem) = Path::new(executable).file_stem() else {
+            continue;
+        };
+        let stem = stem.to_string_lossy();
+        candidates.push(user_dir.join(format!("{}.conf", stem)));
+        candidates.push(user_dir.join(format!("wine-{}.conf", stem)));
+    }
+    let mut result: Vec<PathBuf> = vec![];
+    for path in candidates.into_iter() {
+        if path.is_file() && !result.contains(&path) {
+            result.push(path);
+        }
+    }
+    debug!("mangohud configs: {:?}", result);
+    result
+}
+
+pub fn read(path: &Path) -> Result<MangoHudConfig, Box<dyn Error>> {
+    Ok(parse(&fs::read_to_string(path)?))
+}
+
+pub fn write(path: &Path, config: &MangoHudConfig) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, config.to_string())?;
+    Ok(())
+}
+
+/// `mangohud`, also with a path or as the 32-bit `mangohud.x86`.
+fn is_mangohud(word: &str) -> bool {
+    Path::new(word)
+        .file_name()
+        .is_some_and(|name| name == COMMAND || name == "mangohud.x86")
+}
+
+/// Points the launch options at the config file and runs the game with `mangohud`, or removes
+/// both when `path` is `None`. The other words are left as they are.
+pub fn apply(text: &str, path: Option<&Path>) -> String {
+    let mut launch_options = launch_options::parse(text);
+    match path {
+        Some(path) => {
+            let path = path.to_string_lossy().to_string();
+            match launch_options
+                .env
+                .iter_mut()
+                .find(|(name, _)| name == CONFIG_FILE)
+            {
+                Some(entry) => entry.1 = path,
+                None => launch_options.env.push((CONFIG_FILE.to_string(), path)),
+            }
+            if !launch_options.wrappers.iter().any(|word| is_mangohud(word)) {
+                // Closest to the game, so that it is not drawn over another wrapper such as
+                // gamescope
+                launch_options.wrappers.push(COMMAND.to_string());
+            }
+            launch_options.has_command = true;
+        }
+        None => {
+            launch_options.env.retain(|(name, _)| name != CONFIG_FILE);
+            launch_options.wrappers.retain(|word| !is_mangohud(word));
+        }
+    }
+    launch_options.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let mut config = read(Path::new("tests/mangohud/wine-witcher3.conf")).unwrap();
+        assert!(config.is_set("fps"));
+        assert_eq!(config.value("fps"), None);
+        assert_eq!(config.value("fps_limit"), Some("60"));
+
+        config.set("fps_limit", Some("144".to_string()));
+        config.remove("gpu_stats");
+        config.set("ram", None);
+        assert_eq!(
+            config.to_string(),
+            "fps\nframe_timing\nfps_limit=144\nposition=top-right\nram\n"
+        );
+        assert_eq!(parse(&config.to_string()), config);
+    }
+
+    #[test]
+    fn test_detect() {
+        let executables = vec![
+            "bin/x64/witcher3.exe".to_string(),
+            "witcher3.sh".to_string(),
+        ];
+        assert_eq!(
+            detect(Path::new("tests/mangohud"), "", &executables),
+            vec![PathBuf::from("tests/mangohud/wine-witcher3.conf")]
+        );
+        assert_eq!(
+            detect(
+                Path::new("tests/mangohud"),
+                "MANGOHUD_CONFIGFILE=tests/mangohud/wine-witcher3.conf mangohud %command%",
+                &[]
+            ),
+            vec![PathBuf::from("tests/mangohud/wine-witcher3.conf")]
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let path = Path::new("/home/user/.config/steam-optionx/mangohud/1111111.conf");
+        assert_eq!(
+            apply("gamescope -f -- %command% -novid", Some(path)),
+            "MANGOHUD_CONFIGFILE=/home/user/.config/steam-optionx/mangohud/1111111.conf \
+             gamescope -f -- mangohud %command% -novid"
+        );
+        assert_eq!(
+            apply(
+                "MANGOHUD_CONFIGFILE=/tmp/old.conf PROTON_LOG=1 mangohud %command%",
+                None
+            ),
+            "PROTON_LOG=1 %command%"
+        );
+        assert_eq!(
+            apply("/usr/bin/mangohud gamemoderun %command%", Some(path)),
+            "MANGOHUD_CONFIGFILE=/home/user/.config/steam-optionx/mangohud/1111111.conf \
+             /usr/bin/mangohud gamemoderun %command%"
+        );
+        assert_eq!(apply("mangohud.x86 %command%", None), "%command%");
+        assert_eq!(
+            apply(
+                "FOO=1 MANGOHUD_CONFIGFILE=$HOME/m.conf BAR=~/x mangohud %command%; echo done",
+                Some(path)
+            ),
+            "FOO=1 MANGOHUD_CONFIGFILE=/home/user/.config/steam-optionx/mangohud/1111111.conf \
+             BAR=~/x mangohud %command%; echo done"
+        );
+        assert_eq!(
+            apply(
+                "MANGOHUD_CONFIGFILE=$HOME/m.conf mangohud %command% | tee /tmp/log",
+                None
+            ),
+            "%command% | tee /tmp/log"
+        );
+    }
+}
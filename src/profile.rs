@@ -0,0 +1,233 @@
+use crate::hooks::Hooks;
+use crate::schema::{self, Migration};
+use crate::{config, consts};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+
+pub const DEFAULT: &str = "default";
+const PREFIX: &str = "profile-";
+const MIGRATIONS: &[Migration] = &[v0_to_v1];
+pub const VERSION: u32 = MIGRATIONS.len() as u32;
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(default)]
+    pub version: u32,
+    pub options: Option<BTreeMap<String, String>>,
+    pub locks: Option<Vec<String>>,
+    pub notes: Option<BTreeMap<String, String>>,
+    pub tags: Option<BTreeMap<String, Vec<String>>>,
+    pub hooks: Option<BTreeMap<String, Hooks>>,
+}
+
+impl Profile {
+    pub fn notes(&self) -> BTreeMap<u32, String> {
+        self.notes
+            .iter()
+            .flatten()
+            .filter_map(|(appid, note)| Some((appid.parse::<u32>().ok()?, note.clone())))
+            .collect()
+    }
+
+    pub fn tags(&self) -> BTreeMap<u32, Vec<String>> {
+        self.tags
+            .iter()
+            .flatten()
+            .filter_map(|(appid, tags)| Some((appid.parse::<u32>().ok()?, tags.clone())))
+            .collect()
+    }
+
+    pub fn hooks(&self) -> BTreeMap<u32, Hooks> {
+        self.hooks
+            .iter()
+            .flatten()
+            .filter_map(|(appid, hooks)| Some((appid.parse::<u32>().ok()?, hooks.clone())))
+            .collect()
+    }
+
+    pub fn set_hooks(&mut self, hooks: &BTreeMap<u32, Hooks>) {
+        self.hooks = Some(
+            hooks
+                .iter()
+                .filter(|(_, hooks)| !hooks.is_empty())
+                .map(|(appid, hooks)| (appid.to_string(), hooks.clone()))
+                .collect(),
+        );
+    }
+
+    pub fn set_notes(&mut self, notes: &BTreeMap<u32, String>, tags: &BTreeMap<u32, Vec<String>>) {
+        self.notes = Some(
+            notes
+                .iter()
+                .filter(|(_, note)| !note.trim().is_empty())
+                .map(|(appid, note)| (appid.to_string(), note.clone()))
+                .collect(),
+        );
+        self.tags = Some(
+            tags.iter()
+                .filter(|(_, tags)| !tags.is_empty())
+                .map(|(appid, tags)| (appid.to_string(), tags.clone()))
+                .collect(),
+        );
+    }
+}
+
+/// Splits comma separated tags, dropping empty and duplicate ones.
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut result: Vec<String> = vec![];
+    for tag in text.split(',') {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !result.contains(&tag) {
+            result.push(tag);
+        }
+    }
+    result
+}
+
+/// Version 0 could contain keys that are not app IDs and duplicate locks.
+fn v0_to_v1(table: &mut toml::Table) -> Result<(), Box<dyn Error>> {
+    if let Some(toml::Value::Table(options)) = table.get_mut("options") {
+        options.retain(|appid, _| appid.parse::<u32>().is_ok());
+    }
+    if let Some(toml::Value::Array(locks)) = table.get_mut("locks") {
+        let mut seen = vec![];
+        locks.retain(|lock| {
+            let keep = !seen.contains(lock);
+            seen.push(lock.clone());
+            keep
+        });
+    }
+    Ok(())
+}
+
+/// The default profile keeps the original `profile` file name.
+pub fn config_name(name: &str) -> String {
+    if name == DEFAULT {
+        "profile".to_string()
+    } else {
+        PREFIX.to_owned() + name
+    }
+}
+
+pub fn is_valid_name(name: &str) -> bool {
+    !name.trim().is_empty()
+        && name != DEFAULT
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == ' ')
+}
+
+pub fn active() -> String {
+    let config = config::load();
+    config.profile.unwrap_or(DEFAULT.to_string())
+}
+
+pub fn set_active(name: &str) {
+    let mut config = config::load();
+    config.profile = Some(name.to_string());
+    config::store(config);
+}
+
+pub fn load(name: &str) -> Profile {
+    schema::load(Some(config_name(name).as_str()), MIGRATIONS)
+}
+
+pub fn store(name: &str, profile: &Profile) -> Result<(), Box<dyn Error>> {
+    let profile = Profile {
+        version: VERSION,
+        ..profile.clone()
+    };
+    schema::store(Some(config_name(name).as_str()), profile)?;
+    debug!("profile stored: {}", name);
+    Ok(())
+}
+
+/// Returns the default profile followed by every named profile on disk.
+pub fn list() -> Vec<String> {
+    let mut result = vec![DEFAULT.to_string()];
+    let path = match confy::get_configuration_file_path(consts::CODE_NAME, "profile") {
+        Ok(path) => path,
+        Err(_) => return result,
+    };
+    let entries = match path.parent().map(fs::read_dir) {
+        Some(Ok(entries)) => entries,
+        _ => return result,
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "toml") {
+                let stem = path.file_stem()?.to_str()?;
+                stem.strip_prefix(PREFIX).map(|name| name.to_string())
+            } else {
+                None
+            }
+        })
+        .collect();
+    names.sort();
+    result.append(&mut names);
+    result
+}
+
+pub fn delete(name: &str) -> Result<(), Box<dyn Error>> {
+    let path = confy::get_configuration_file_path(consts::CODE_NAME, config_name(name).as_str())?;
+    fs::remove_file(path)?;
+    debug!("profile deleted: {}", name);
+    Ok(())
+}
+
+pub fn rename(name: &str, new_name: &str) -> Result<(), Box<dyn Error>> {
+    let from = confy::get_configuration_file_path(consts::CODE_NAME, config_name(name).as_str())?;
+    let to = confy::get_configuration_file_path(consts::CODE_NAME, config_name(new_name).as_str())?;
+    if to.exists() {
+        return Err(format!("profile already exists: {}", new_name).into());
+    }
+    fs::rename(from, to)?;
+    debug!("profile renamed: {} -> {}", name, new_name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_name() {
+        assert_eq!(config_name(DEFAULT), "profile");
+        assert_eq!(config_name("streaming"), "profile-streaming");
+        assert!(is_valid_name("benchmark 2"));
+        assert!(!is_valid_name(DEFAULT));
+        assert!(!is_valid_name("../profile"));
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            parse_tags(" Proton, wined3d,,proton "),
+            vec!["proton".to_string(), "wined3d".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_v0_to_v1() {
+        let mut table: toml::Table =
+            "locks = [\"1111111\", \"1111111\"]\n[options]\n1111111 = \"gamemoderun %command%\"\nfoo = \"\""
+                .parse()
+                .unwrap();
+        schema::migrate(&mut table, MIGRATIONS).unwrap();
+        let profile: Profile = table.try_into().unwrap();
+        assert_eq!(profile.version, 1);
+        assert_eq!(profile.locks, Some(vec!["1111111".to_string()]));
+        assert_eq!(
+            profile.options,
+            Some(BTreeMap::from([(
+                "1111111".to_string(),
+                "gamemoderun %command%".to_string()
+            )]))
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::profile::{self, Profile};
+use gix::objs::tree::{Entry, EntryKind};
+use log::debug;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+const NAME: &str = "steam-optionx";
+const EMAIL: &str = "steam-optionx@localhost";
+
+#[derive(Debug, PartialEq)]
+pub struct Revision {
+    pub id: String,
+    pub time: String,
+    pub launch_options: String,
+}
+
+fn filename(name: &str) -> String {
+    profile::config_name(name) + ".toml"
+}
+
+pub fn repository_dir() -> Result<PathBuf, Box<dyn Error>> {
+    let path = confy::get_configuration_file_path(crate::consts::CODE_NAME, "profile")?;
+    Ok(path.with_file_name("profile.git"))
+}
+
+fn open(dir: &Path) -> Result<gix::Repository, Box<dyn Error>> {
+    if dir.join("HEAD").is_file() {
+        Ok(gix::open(dir)?)
+    } else {
+        debug!("initializing profile repository: {}", dir.display());
+        Ok(gix::init_bare(dir)?)
+    }
+}
+
+pub fn message(
+    name: &str,
+    previous: &BTreeMap<String, String>,
+    current: &BTreeMap<String, String>,
+    app_names: &BTreeMap<u32, String>,
+) -> String {
+    let mut changes = vec![];
+    for (appid, launch_options) in current.iter() {
+        let previous_launch_options = previous.get(appid).map_or("", |s| s.as_str());
+        if previous_launch_options == launch_options {
+            continue;
+        }
+        let name = appid
+            .parse::<u32>()
+            .ok()
+            .and_then(|appid| app_names.get(&appid))
+            .map_or("", |name| name.as_str());
+        changes.push(format!(
+            "{} {}: \"{}\" -> \"{}\"",
+            appid, name, previous_launch_options, launch_options
+        ));
+    }
+    if changes.is_empty() {
+        format!("Save {} profile", name)
+    } else {
+        format!(
+            "Update launch options for {} app(s) in {} profile\n\n{}\n",
+            changes.len(),
+            name,
+            changes.join("\n")
+        )
+    }
+}
+
+/// Commits the named profile unless it is identical to the one in the latest commit.
+pub fn commit(
+    dir: &Path,
+    name: &str,
+    profile: &Profile,
+    message: &str,
+) -> Result<(), Box<dyn Error>> {
+    let repo = open(dir)?;
+    let filename = filename(name);
+    let contents = toml::to_string(profile)?;
+    let blob = repo.write_blob(contents.as_bytes())?.detach();
+    let parent = repo.head_commit().ok();
+    // Keep the other profiles from the latest commit
+    let mut entries = vec![];
+    if let Some(parent) = &parent {
+        let tree = parent.tree()?.decode()?.to_owned();
+        for entry in tree.entries.into_iter() {
+            if entry.filename == filename.as_str() {
+                if entry.oid == blob {
+                    debug!("profile unchanged");
+                    return Ok(());
+                }
+            } else {
+                entries.push(entry);
+            }
+        }
+    }
+    entries.push(Entry {
+        mode: EntryKind::Blob.into(),
+        filename: filename.into(),
+        oid: blob,
+    });
+    entries.sort();
+    let tree = repo.write_object(&gix::objs::Tree { entries })?.detach();
+    let signature = gix::actor::Signature {
+        name: NAME.into(),
+        email: EMAIL.into(),
+        time: gix::date::Time::now_local_or_utc(),
+    };
+    let mut time = gix::date::parse::TimeBuf::default();
+    let signature = signature.to_ref(&mut time);
+    let parents: Vec<gix::ObjectId> = parent.into_iter().map(|commit| commit.id).collect();
+    let id = repo.commit_as(signature, signature, "HEAD", message, tree, parents)?;
+    debug!("profile committed: {}", id);
+    Ok(())
+}
+
+/// Returns every distinct launch options value of an app in the named profile, newest first.
+pub fn app_history(dir: &Path, name: &str, appid: u32) -> Result<Vec<Revision>, Box<dyn Error>> {
+    let mut result: Vec<Revision> = vec![];
+    if !dir.join("HEAD").is_file() {
+        return Ok(result);
+    }
+    let repo = open(dir)?;
+    let filename = filename(name);
+    let head = match repo.head_id() {
+        Ok(head) => head,
+        Err(_) => return Ok(result),
+    };
+    for info in head.ancestors().all()? {
+        let commit = info?.object()?;
+        let tree = commit.tree()?;
+        let entry = match tree.find_entry(filename.as_str()) {
+            Some(entry) => entry,
+            None => continue,
+        };
+        let contents = entry.object()?.detach().data;
+        let profile: Profile = toml::from_str(&String::from_utf8_lossy(&contents))?;
+        let launch_options = profile
+            .options
+            .unwrap_or_default()
+            .remove(&appid.to_string())
+            .unwrap_or_default();
+        let revision = Revision {
+            id: commit.id.to_hex_with_len(7).to_string(),
+            time: commit.time()?.format(gix::date::time::format::ISO8601),
+            launch_options,
+        };
+        // Keep the oldest commit of consecutive identical values
+        match result.last_mut() {
+            Some(last) if last.launch_options == revision.launch_options => *last = revision,
+            _ => result.push(revision),
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_and_app_history() {
+        let dir = std::env::temp_dir().join(format!("steam-optionx-{}.git", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for launch_options in ["gamemoderun %command%", "gamemoderun %command%", ""] {
+            let profile = Profile {
+                options: Some(BTreeMap::from([(
+                    "1111111".to_string(),
+                    launch_options.to_string(),
+                )])),
+                ..Default::default()
+            };
+            commit(&dir, "test", &profile, "test").unwrap();
+            commit(&dir, profile::DEFAULT, &Profile::default(), "test").unwrap();
+        }
+        let history = app_history(&dir, "test", 1111111).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|revision| revision.launch_options.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["", "gamemoderun %command%"]
+        );
+    }
+}
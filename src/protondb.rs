@@ -0,0 +1,281 @@
+//! Tiers and launch options from a ProtonDB report dump, such as `reports_piiremoved.json` from
+//! the published data archives, indexed by app ID so they work offline.
+
+use crate::schema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use strum_macros::{Display, EnumString};
+
+const CONFIG_NAME: &str = "protondb";
+/// Launch options kept per app, most reported first.
+const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display, EnumString, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(ascii_case_insensitive)]
+pub enum Tier {
+    Borked,
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub launch_options: String,
+    pub reports: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppReports {
+    pub tier: Tier,
+    pub reports: u32,
+    #[serde(default)]
+    pub suggestions: Vec<Suggestion>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProtonDb {
+    /// Seconds since the Unix epoch
+    pub imported: u64,
+    pub apps: BTreeMap<String, AppReports>,
+}
+
+impl ProtonDb {
+    pub fn by_appid(&self) -> BTreeMap<u32, AppReports> {
+        self.apps
+            .iter()
+            .filter_map(|(appid, reports)| Some((appid.parse().ok()?, reports.clone())))
+            .collect()
+    }
+}
+
+/// App IDs are strings in most dumps and numbers in some.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AppId {
+    Number(u32),
+    Text(String),
+}
+
+impl AppId {
+    fn get(&self) -> Option<u32> {
+        match self {
+            AppId::Number(appid) => Some(*appid),
+            AppId::Text(appid) => appid.trim().parse().ok(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SteamApp {
+    #[serde(rename = "appId")]
+    app_id: Option<AppId>,
+}
+
+#[derive(Deserialize)]
+struct ReportApp {
+    steam: Option<SteamApp>,
+}
+
+/// Answers of the current report form, "yes" or "no".
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Responses {
+    verdict: Option<String>,
+    verdict_oob: Option<String>,
+    launch_options: Option<String>,
+    audio_faults: Option<String>,
+    graphical_faults: Option<String>,
+    input_faults: Option<String>,
+    performance_faults: Option<String>,
+    save_game_faults: Option<String>,
+    significant_bugs: Option<String>,
+    stability_faults: Option<String>,
+    windowing_faults: Option<String>,
+}
+
+/// Only the fields used here, the rest of a report is skipped while reading.
+#[derive(Deserialize)]
+struct Report {
+    /// Older reports are flat with a rating
+    #[serde(rename = "appId")]
+    app_id: Option<AppId>,
+    rating: Option<String>,
+    app: Option<ReportApp>,
+    responses: Option<Responses>,
+}
+
+impl Responses {
+    fn faults(&self) -> usize {
+        [
+            &self.audio_faults,
+            &self.graphical_faults,
+            &self.input_faults,
+            &self.performance_faults,
+            &self.save_game_faults,
+            &self.significant_bugs,
+            &self.stability_faults,
+            &self.windowing_faults,
+        ]
+        .iter()
+        .filter(|answer| answer.as_deref() == Some("yes"))
+        .count()
+    }
+
+    /// Newer reports have no rating, it is approximated from the verdict and the faults.
+    fn tier(&self) -> Option<Tier> {
+        if self.verdict.as_deref() == Some("no") {
+            return Some(Tier::Borked);
+        }
+        if self.verdict_oob.as_deref() == Some("yes") {
+            return Some(Tier::Platinum);
+        }
+        if self.verdict.as_deref() != Some("yes") {
+            return None;
+        }
+        Some(match self.faults() {
+            0 => Tier::Gold,
+            1 | 2 => Tier::Silver,
+            _ => Tier::Bronze,
+        })
+    }
+}
+
+impl Report {
+    fn appid(&self) -> Option<u32> {
+        self.app_id
+            .as_ref()
+            .or_else(|| self.app.as_ref()?.steam.as_ref()?.app_id.as_ref())?
+            .get()
+    }
+
+    fn tier(&self) -> Option<Tier> {
+        match &self.rating {
+            Some(rating) => rating.parse().ok(),
+            None => self.responses.as_ref()?.tier(),
+        }
+    }
+
+    fn launch_options(&self) -> Option<String> {
+        let text = self.responses.as_ref()?.launch_options.as_ref()?;
+        let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+        (!text.is_empty() && text != "%command%").then_some(text)
+    }
+}
+
+/// The median tier of the reports, which keeps a few outliers from moving it.
+fn median(tiers: &mut [Tier]) -> Option<Tier> {
+    tiers.sort();
+    tiers.get(tiers.len() / 2).copied()
+}
+
+fn index(reports: Vec<Report>, imported: u64) -> ProtonDb {
+    let mut tiers: BTreeMap<u32, Vec<Tier>> = BTreeMap::new();
+    let mut launch_options: BTreeMap<u32, BTreeMap<String, u32>> = BTreeMap::new();
+    for report in reports.iter() {
+        let Some(appid) = report.appid() else {
+            continue;
+        };
+        if let Some(tier) = report.tier() {
+            tiers.entry(appid).or_default().push(tier);
+        }
+        if let Some(text) = report.launch_options() {
+            *launch_options
+                .entry(appid)
+                .or_default()
+                .entry(text)
+                .or_default() += 1;
+        }
+    }
+    let apps = tiers
+        .into_iter()
+        .filter_map(|(appid, mut tiers)| {
+            let mut suggestions: Vec<Suggestion> = launch_options
+                .remove(&appid)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(launch_options, reports)| Suggestion {
+                    launch_options,
+                    reports,
+                })
+                .collect();
+            suggestions.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.reports));
+            suggestions.truncate(MAX_SUGGESTIONS);
+            let reports = AppReports {
+                tier: median(&mut tiers)?,
+                reports: tiers.len() as u32,
+                suggestions,
+            };
+            Some((appid.to_string(), reports))
+        })
+        .collect();
+    ProtonDb { imported, apps }
+}
+
+/// Reads a JSON array of reports and indexes it.
+pub fn import(path: &Path, imported: u64) -> Result<ProtonDb, Box<dyn Error>> {
+    let reports: Vec<Report> = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+    Ok(index(reports, imported))
+}
+
+pub fn load() -> ProtonDb {
+    schema::load(Some(CONFIG_NAME), &[])
+}
+
+pub fn store(protondb: &ProtonDb) -> Result<(), Box<dyn Error>> {
+    schema::store(Some(CONFIG_NAME), protondb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import() {
+        let protondb = import(Path::new("tests/protondb/reports.json"), 1).unwrap();
+        let apps = protondb.by_appid();
+        assert_eq!(apps.len(), 3);
+
+        let witcher = &apps[&1111111];
+        assert_eq!(witcher.tier, Tier::Gold);
+        assert_eq!(witcher.reports, 4);
+        assert_eq!(
+            witcher.suggestions,
+            vec![
+                Suggestion {
+                    launch_options: "PROTON_USE_WINED3D=1 %command%".to_string(),
+                    reports: 2,
+                },
+                Suggestion {
+                    launch_options: "gamemoderun %command% -novid".to_string(),
+                    reports: 1,
+                },
+            ]
+        );
+        assert_eq!(apps[&2222222].tier, Tier::Borked);
+        assert_eq!(apps[&3333333].tier, Tier::Platinum);
+        assert!(apps[&3333333].suggestions.is_empty());
+
+        let stored: ProtonDb = toml::from_str(&toml::to_string(&protondb).unwrap()).unwrap();
+        assert_eq!(stored.by_appid(), apps);
+    }
+
+    #[test]
+    fn test_tier() {
+        assert_eq!("platinum".parse::<Tier>().unwrap(), Tier::Platinum);
+        assert!(Tier::Gold > Tier::Silver);
+        assert_eq!(
+            median(&mut [Tier::Borked, Tier::Gold, Tier::Platinum, Tier::Gold]),
+            Some(Tier::Gold)
+        );
+        assert_eq!(median(&mut []), None);
+    }
+}
@@ -0,0 +1,391 @@
+//! Filter queries such as `locked:false (env:PROTON_LOG OR contains:gamescope) NOT tag:broken`.
+//!
+//! Words are fuzzy matched against app names and matched against notes and tags. Terms are joined
+//! with `AND` by default.
+
+use crate::fuzzy;
+use crate::launch_options;
+use crate::protondb::Tier;
+use regex::Regex;
+use std::path::Path;
+
+pub struct Candidate<'a> {
+    pub appid: u32,
+    pub name: &'a str,
+    pub launch_options: &'a str,
+    pub lock: bool,
+    pub library: &'a Path,
+    pub notes: Option<&'a String>,
+    pub tags: Option<&'a Vec<String>>,
+    pub tier: Option<Tier>,
+}
+
+#[derive(Debug)]
+pub enum Term {
+    Word(String),
+    Id(u32),
+    Name(String),
+    NameRegex(Regex),
+    HasOptions(bool),
+    Locked(bool),
+    Contains(String),
+    Env(String, Option<String>),
+    Library(String),
+    Tag(String),
+    Note(String),
+    /// `None` matches apps without imported ProtonDB reports
+    Tier(Option<Tier>),
+}
+
+#[derive(Debug)]
+pub enum Query {
+    All,
+    Term(Term),
+    Not(Box<Query>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Word(String),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut result = vec![];
+    let mut word = String::new();
+    let mut quoted = false;
+    // Whether the current word had quotes, which makes "OR" a word instead of an operator
+    let mut had_quote = false;
+    let mut in_word = false;
+    let push = |result: &mut Vec<Token>, word: &mut String, quoted: bool| {
+        let word = std::mem::take(word);
+        result.push(match word.as_str() {
+            "AND" | "&&" if !quoted => Token::And,
+            "OR" | "||" | "|" if !quoted => Token::Or,
+            "NOT" | "!" if !quoted => Token::Not,
+            _ => Token::Word(word),
+        });
+    };
+    for c in text.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                had_quote = true;
+                in_word = true;
+            }
+            c if quoted => word.push(c),
+            '(' | ')' | ' ' | '\t' => {
+                if in_word {
+                    push(&mut result, &mut word, had_quote);
+                    had_quote = false;
+                    in_word = false;
+                }
+                match c {
+                    '(' => result.push(Token::Open),
+                    ')' => result.push(Token::Close),
+                    _ => {}
+                }
+            }
+            '-' | '!' if !in_word => result.push(Token::Not),
+            c => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quoted {
+        return Err("Unterminated quote".to_string());
+    }
+    if in_word {
+        push(&mut result, &mut word, had_quote);
+    }
+    Ok(result)
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "1" => Ok(true),
+        "false" | "no" | "0" => Ok(false),
+        _ => Err(format!("Expected true or false: {}", value)),
+    }
+}
+
+fn parse_term(word: &str) -> Result<Term, String> {
+    let Some((key, value)) = word.split_once(':') else {
+        return Ok(Term::Word(word.to_lowercase()));
+    };
+    let term = match key.to_lowercase().as_str() {
+        "id" => Term::Id(
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid app ID: {}", value))?,
+        ),
+        "name" => match value.strip_prefix('/').and_then(|v| v.strip_suffix('/')) {
+            Some(pattern) => Term::NameRegex(
+                Regex::new(&format!("(?i){}", pattern)).map_err(|err| err.to_string())?,
+            ),
+            None => Term::Name(value.to_lowercase()),
+        },
+        "has" | "empty" => {
+            if value != "options" {
+                return Err(format!("Expected {}:options", key));
+            }
+            Term::HasOptions(key == "has")
+        }
+        "locked" => Term::Locked(parse_bool(value)?),
+        "contains" => Term::Contains(value.to_lowercase()),
+        "env" => match value.split_once('=') {
+            Some((name, value)) => Term::Env(name.to_string(), Some(value.to_string())),
+            None => Term::Env(value.to_string(), None),
+        },
+        "library" => Term::Library(value.to_string()),
+        "tag" => Term::Tag(value.to_lowercase()),
+        "note" | "notes" => Term::Note(value.to_lowercase()),
+        "tier" => Term::Tier(match value.to_lowercase().as_str() {
+            "none" => None,
+            value => Some(
+                value
+                    .parse()
+                    .map_err(|_| format!("Invalid ProtonDB tier: {}", value))?,
+            ),
+        }),
+        // Not a known key, e.g. "Portal:"
+        _ => Term::Word(word.to_lowercase()),
+    };
+    Ok(term)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        self.position += 1;
+        self.tokens.get(self.position - 1)
+    }
+
+    fn or(&mut self) -> Result<Query, String> {
+        let mut queries = vec![self.and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            queries.push(self.and()?);
+        }
+        Ok(if queries.len() == 1 {
+            queries.remove(0)
+        } else {
+            Query::Or(queries)
+        })
+    }
+
+    fn and(&mut self) -> Result<Query, String> {
+        let mut queries = vec![self.not()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                }
+                Some(Token::Open | Token::Not | Token::Word(_)) => {}
+                _ => break,
+            }
+            queries.push(self.not()?);
+        }
+        Ok(if queries.len() == 1 {
+            queries.remove(0)
+        } else {
+            Query::And(queries)
+        })
+    }
+
+    fn not(&mut self) -> Result<Query, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(Query::Not(Box::new(self.not()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Query, String> {
+        match self.next() {
+            Some(Token::Open) => {
+                let query = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(query),
+                    _ => Err("Missing closing parenthesis".to_string()),
+                }
+            }
+            Some(Token::Word(word)) => {
+                let word = word.clone();
+                Ok(Query::Term(parse_term(&word)?))
+            }
+            Some(token) => Err(format!("Unexpected {:?}", token)),
+            None => Err("Unexpected end of filter".to_string()),
+        }
+    }
+}
+
+pub fn parse(text: &str) -> Result<Query, String> {
+    let tokens = tokenize(text)?;
+    if tokens.is_empty() {
+        return Ok(Query::All);
+    }
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+    let query = parser.or()?;
+    match parser.peek() {
+        None => Ok(query),
+        Some(token) => Err(format!("Unexpected {:?}", token)),
+    }
+}
+
+impl Term {
+    fn matches(&self, candidate: &Candidate) -> bool {
+        match self {
+            Term::Word(word) => {
+                fuzzy::find(word, candidate.name).is_some()
+                    || candidate.appid.to_string() == *word
+                    || candidate
+                        .notes
+                        .is_some_and(|notes| notes.to_lowercase().contains(word))
+                    || candidate
+                        .tags
+                        .is_some_and(|tags| tags.iter().any(|tag| tag.contains(word)))
+            }
+            Term::Id(appid) => candidate.appid == *appid,
+            Term::Name(name) => candidate.name.to_lowercase() == *name,
+            Term::NameRegex(regex) => regex.is_match(candidate.name),
+            Term::HasOptions(has) => candidate.launch_options.trim().is_empty() != *has,
+            Term::Locked(lock) => candidate.lock == *lock,
+            Term::Contains(text) => candidate.launch_options.to_lowercase().contains(text),
+            Term::Env(name, value) => {
+                let launch_options = launch_options::parse(candidate.launch_options);
+                match (launch_options.env(name), value) {
+                    (Some(actual), Some(value)) => actual == value,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                }
+            }
+            Term::Library(library) => candidate.library.starts_with(library),
+            Term::Tag(tag) => candidate.tags.is_some_and(|tags| tags.contains(tag)),
+            Term::Note(note) => candidate
+                .notes
+                .is_some_and(|notes| notes.to_lowercase().contains(note)),
+            Term::Tier(tier) => candidate.tier == *tier,
+        }
+    }
+}
+
+impl Query {
+    /// Words that are not negated, used to rank and highlight app names.
+    pub fn words(&self) -> Vec<&str> {
+        match self {
+            Query::Term(Term::Word(word)) => vec![word],
+            Query::And(queries) | Query::Or(queries) => {
+                queries.iter().flat_map(|query| query.words()).collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    pub fn matches(&self, candidate: &Candidate) -> bool {
+        match self {
+            Query::All => true,
+            Query::Term(term) => term.matches(candidate),
+            Query::Not(query) => !query.matches(candidate),
+            Query::And(queries) => queries.iter().all(|query| query.matches(candidate)),
+            Query::Or(queries) => queries.iter().any(|query| query.matches(candidate)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_matches() {
+        let library = PathBuf::from("/mnt/ssd/SteamLibrary");
+        let tags = vec!["proton".to_string()];
+        let candidate = Candidate {
+            appid: 1111111,
+            name: "The Witcher 3: Wild Hunt",
+            launch_options: "PROTON_LOG=1 gamescope -- %command%",
+            lock: false,
+            library: &library,
+            notes: None,
+            tags: Some(&tags),
+            tier: Some(Tier::Gold),
+        };
+        let matches = |text: &str| parse(text).unwrap().matches(&candidate);
+        assert!(matches(""));
+        assert!(matches("witcher"));
+        assert!(matches("Witcher 3:"));
+        assert!(matches("witcher3"));
+        assert!(matches("id:1111111"));
+        assert!(matches("name:\"the witcher 3: wild hunt\""));
+        assert!(!matches("name:witcher"));
+        assert!(matches("name:\"/^the witcher \\d/\""));
+        assert!(matches("has:options AND locked:false"));
+        assert!(!matches("empty:options"));
+        assert!(matches("contains:gamescope env:PROTON_LOG"));
+        assert!(matches("env:PROTON_LOG=1"));
+        assert!(!matches("env:DXVK_ASYNC"));
+        assert!(matches("library:/mnt/ssd"));
+        assert!(matches("tag:proton"));
+        assert!(matches("tier:Gold"));
+        assert!(!matches("tier:none"));
+        assert!(parse("tier:diamond").is_err());
+        assert!(matches("portal OR (witcher NOT locked:true)"));
+        assert!(!matches("-witcher"));
+        assert!(!matches("NOT (witcher OR portal)"));
+    }
+
+    #[test]
+    fn test_words() {
+        let query = parse("witcher OR (portal NOT half) tag:proton").unwrap();
+        assert_eq!(query.words(), vec!["witcher", "portal"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("(witcher").is_err());
+        assert!(parse("witcher)").is_err());
+        assert!(parse("locked:maybe").is_err());
+        assert!(parse("name:\"witcher").is_err());
+        assert!(parse("witcher OR").is_err());
+    }
+
+    #[test]
+    fn test_quoted_operators() {
+        assert_eq!(
+            tokenize("\"OR\" NOT or").unwrap(),
+            vec![
+                Token::Word("OR".to_string()),
+                Token::Not,
+                Token::Word("or".to_string())
+            ]
+        );
+        assert!(parse("\"OR\"").is_ok());
+        assert_eq!(
+            tokenize("\"NOT\" witcher").unwrap(),
+            vec![
+                Token::Word("NOT".to_string()),
+                Token::Word("witcher".to_string())
+            ]
+        );
+    }
+}
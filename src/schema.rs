@@ -0,0 +1,163 @@
+use crate::consts;
+use log::{debug, warn};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Upgrades a file from the version at its index to the next one.
+pub type Migration = fn(&mut toml::Table) -> Result<(), Box<dyn Error>>;
+
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(vec![]);
+/// Files written by a newer version, which are neither read nor overwritten.
+static NEWER: Mutex<Vec<PathBuf>> = Mutex::new(vec![]);
+
+/// A file with a version this build does not know how to read.
+#[derive(Debug)]
+pub struct NewerVersion(pub usize);
+
+impl std::fmt::Display for NewerVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "unsupported version: {}", self.0)
+    }
+}
+
+impl Error for NewerVersion {}
+
+/// Returns and clears the warnings raised while loading files.
+pub fn take_warnings() -> Vec<String> {
+    WARNINGS
+        .lock()
+        .map(|mut warnings| std::mem::take(&mut *warnings))
+        .unwrap_or_default()
+}
+
+pub fn push_warning(warning: String) {
+    warn!("{}", warning);
+    if let Ok(mut warnings) = WARNINGS.lock() {
+        warnings.push(warning);
+    }
+}
+
+pub fn migrate(table: &mut toml::Table, migrations: &[Migration]) -> Result<(), Box<dyn Error>> {
+    let version = match table.get("version") {
+        None => 0,
+        Some(toml::Value::Integer(version)) if *version >= 0 => *version as usize,
+        Some(version) => return Err(format!("invalid version: {}", version).into()),
+    };
+    if version > migrations.len() {
+        return Err(NewerVersion(version).into());
+    }
+    for (i, migration) in migrations.iter().enumerate().skip(version) {
+        debug!("migrating from version {} to {}", i, i + 1);
+        migration(table)?;
+        table.insert("version".to_string(), toml::Value::Integer(i as i64 + 1));
+    }
+    Ok(())
+}
+
+fn read<T: DeserializeOwned>(path: &Path, migrations: &[Migration]) -> Result<T, Box<dyn Error>> {
+    let contents = fs::read_to_string(path)?;
+    let mut table: toml::Table = contents.parse()?;
+    migrate(&mut table, migrations)?;
+    Ok(table.try_into()?)
+}
+
+fn preserve(path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let mut corrupt = path.as_os_str().to_owned();
+    corrupt.push(".corrupt");
+    let corrupt = PathBuf::from(corrupt);
+    fs::rename(path, &corrupt)?;
+    Ok(corrupt)
+}
+
+/// Keeps `store` from overwriting a file, as one written by a newer version.
+pub fn leave_unchanged(path: PathBuf) {
+    if let Ok(mut newer) = NEWER.lock() {
+        newer.push(path);
+    }
+}
+
+/// Loads and migrates a file, falling back to defaults if it is missing.
+/// Unreadable files are kept under a `.corrupt` name and reported as warnings.
+pub fn load<T: DeserializeOwned + Default>(
+    config_name: Option<&str>,
+    migrations: &[Migration],
+) -> T {
+    let path = match confy::get_configuration_file_path(consts::CODE_NAME, config_name) {
+        Ok(path) => path,
+        Err(err) => {
+            warn!("error locating configuration: {}", err);
+            return T::default();
+        }
+    };
+    if !path.is_file() {
+        return T::default();
+    }
+    match read(&path, migrations) {
+        Ok(value) => value,
+        Err(err) if err.is::<NewerVersion>() => {
+            push_warning(format!(
+                "{} was written by a newer version of {} and is left unchanged, \
+                 changes are not saved until it is updated",
+                path.display(),
+                consts::APP_NAME
+            ));
+            leave_unchanged(path);
+            T::default()
+        }
+        Err(err) => {
+            let warning = match preserve(&path) {
+                Ok(corrupt) => format!(
+                    "{} could not be read and was reset, kept as {}: {}",
+                    path.display(),
+                    corrupt.display(),
+                    err
+                ),
+                Err(rename_err) => format!(
+                    "{} could not be read and was reset: {} ({})",
+                    path.display(),
+                    err,
+                    rename_err
+                ),
+            };
+            push_warning(warning);
+            T::default()
+        }
+    }
+}
+
+pub fn store<T: Serialize>(config_name: Option<&str>, value: T) -> Result<(), Box<dyn Error>> {
+    let path = confy::get_configuration_file_path(consts::CODE_NAME, config_name)?;
+    if NEWER.lock().is_ok_and(|newer| newer.contains(&path)) {
+        return Err(format!("{} is from a newer version", path.display()).into());
+    }
+    confy::store(consts::CODE_NAME, config_name, value)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rename_key(table: &mut toml::Table) -> Result<(), Box<dyn Error>> {
+        if let Some(value) = table.remove("old") {
+            table.insert("new".to_string(), value);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate() {
+        let mut table: toml::Table = "old = 1".parse().unwrap();
+        migrate(&mut table, &[rename_key]).unwrap();
+        assert_eq!(table, "new = 1\nversion = 1".parse().unwrap());
+        migrate(&mut table, &[rename_key]).unwrap();
+        assert_eq!(table.get("new"), Some(&toml::Value::Integer(1)));
+        let mut table: toml::Table = "version = 2".parse().unwrap();
+        let err = migrate(&mut table, &[rename_key]).unwrap_err();
+        assert!(err.is::<NewerVersion>());
+    }
+}
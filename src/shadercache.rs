@@ -0,0 +1,104 @@
+//! Steam's shader pre-caching data under `steamapps/shadercache/<appid>` in each library.
+
+use crate::fsutil;
+use log::debug;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderCache {
+    pub appid: u32,
+    pub library: PathBuf,
+    pub size: u64,
+}
+
+pub fn cache_dir(library: &Path, appid: u32) -> PathBuf {
+    library
+        .join("steamapps/shadercache")
+        .join(appid.to_string())
+}
+
+/// Measures every shader cache in the libraries, including those of uninstalled apps.
+pub fn scan(libraries: &[PathBuf]) -> Vec<ShaderCache> {
+    let mut result = vec![];
+    for library in libraries.iter() {
+        let Ok(entries) = fs::read_dir(library.join("steamapps/shadercache")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(appid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+                continue;
+            };
+            if entry.path().is_dir() {
+                result.push(ShaderCache {
+                    appid,
+                    library: library.clone(),
+                    size: fsutil::dir_size(&entry.path()),
+                });
+            }
+        }
+    }
+    result.sort_by(|a, b| b.size.cmp(&a.size).then(a.appid.cmp(&b.appid)));
+    debug!("shader caches: {}", result.len());
+    result
+}
+
+/// Bytes per app in the library it is installed in, `installed` holding the library of each
+/// app. Caches left in other libraries are only listed per library.
+pub fn sizes(caches: &[ShaderCache], installed: &BTreeMap<u32, PathBuf>) -> BTreeMap<u32, u64> {
+    caches
+        .iter()
+        .filter(|cache| installed.get(&cache.appid) == Some(&cache.library))
+        .map(|cache| (cache.appid, cache.size))
+        .collect()
+}
+
+/// Bytes per library.
+pub fn totals(caches: &[ShaderCache]) -> BTreeMap<PathBuf, u64> {
+    let mut result = BTreeMap::new();
+    for cache in caches.iter() {
+        *result.entry(cache.library.clone()).or_default() += cache.size;
+    }
+    result
+}
+
+/// Steam downloads or rebuilds the cache the next time the app runs.
+pub fn delete(cache: &ShaderCache) -> io::Result<()> {
+    let path = cache_dir(&cache.library, cache.appid);
+    debug!("deleting shader cache: {}", path.display());
+    fs::remove_dir_all(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan() {
+        let library = PathBuf::from("tests/library");
+        let caches = scan(std::slice::from_ref(&library));
+        assert_eq!(
+            caches,
+            vec![
+                ShaderCache {
+                    appid: 1111111,
+                    library: library.clone(),
+                    size: 1000,
+                },
+                ShaderCache {
+                    appid: 5555555,
+                    library: library.clone(),
+                    size: 300,
+                },
+            ]
+        );
+        let installed = BTreeMap::from([
+            (1111111, PathBuf::from("/mnt/games")),
+            (5555555, library.clone()),
+        ]);
+        assert_eq!(sizes(&caches, &installed), BTreeMap::from([(5555555, 300)]));
+        assert_eq!(totals(&caches), BTreeMap::from([(library, 1300)]));
+    }
+}
@@ -0,0 +1,194 @@
+use crate::schema;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const TEAM_FILE: &str = "team.toml";
+const CONTRIBUTIONS_DIR: &str = "contributions";
+const SYNC_NAME: &str = "team";
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub launch_options: String,
+    /// Seconds since the Unix epoch
+    pub modified: u64,
+    pub author: Option<String>,
+    /// Team entry stamp a contribution is based on
+    pub base: Option<u64>,
+    pub notes: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TeamProfile {
+    pub apps: BTreeMap<String, Entry>,
+}
+
+/// Team entries as they were when last pulled or proposed, stored locally.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    pub synced: BTreeMap<String, Entry>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Status {
+    Same,
+    /// Local options are missing from the team profile
+    Missing,
+    /// Team options changed since the last sync
+    Behind,
+    /// Local options changed since the last sync
+    Ahead,
+    /// Both local and team options changed since the last sync
+    Conflict,
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+pub fn load_sync() -> SyncState {
+    schema::load(Some(SYNC_NAME), &[])
+}
+
+pub fn store_sync(sync: &SyncState) -> Result<(), Box<dyn Error>> {
+    schema::store(Some(SYNC_NAME), sync)
+}
+
+pub fn user_name() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn read(path: &Path) -> Result<TeamProfile, Box<dyn Error>> {
+    if !path.is_file() {
+        return Ok(TeamProfile::default());
+    }
+    let contents = fs::read_to_string(path)?;
+    Ok(toml::from_str(&contents)?)
+}
+
+pub fn read_team_profile(team_dir: &str) -> Result<TeamProfile, Box<dyn Error>> {
+    read(&PathBuf::from(team_dir).join(TEAM_FILE))
+}
+
+/// Returns every contribution keyed by user name.
+pub fn read_contributions(team_dir: &str) -> Result<BTreeMap<String, TeamProfile>, Box<dyn Error>> {
+    let mut result = BTreeMap::new();
+    let dir = PathBuf::from(team_dir).join(CONTRIBUTIONS_DIR);
+    if !dir.is_dir() {
+        return Ok(result);
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "toml")
+            && let Some(user) = path.file_stem().and_then(|stem| stem.to_str())
+        {
+            result.insert(user.to_string(), read(&path)?);
+        }
+    }
+    Ok(result)
+}
+
+pub fn status(local: &str, team: Option<&Entry>, synced: Option<&Entry>) -> Status {
+    let team = match team {
+        Some(team) => team,
+        None if local.trim().is_empty() => return Status::Same,
+        None => return Status::Missing,
+    };
+    if local.trim() == team.launch_options.trim() {
+        return Status::Same;
+    }
+    match synced {
+        Some(synced) => {
+            let team_changed = team.modified > synced.modified;
+            let local_changed = local.trim() != synced.launch_options.trim();
+            match (team_changed, local_changed) {
+                (true, true) => Status::Conflict,
+                (true, false) => Status::Behind,
+                _ => Status::Ahead,
+            }
+        }
+        None => Status::Conflict,
+    }
+}
+
+/// Merges local entries into the current user's contribution file.
+pub fn propose(
+    team_dir: &str,
+    entries: BTreeMap<u32, Entry>,
+    team: &TeamProfile,
+) -> Result<(), Box<dyn Error>> {
+    let dir = PathBuf::from(team_dir).join(CONTRIBUTIONS_DIR);
+    fs::create_dir_all(&dir)?;
+    let user = user_name();
+    let path = dir.join(format!("{}.toml", user));
+    let mut contribution = read(&path)?;
+    let modified = now();
+    for (appid, entry) in entries.into_iter() {
+        let appid = appid.to_string();
+        let base = team.apps.get(&appid).map(|entry| entry.modified);
+        contribution.apps.insert(
+            appid,
+            Entry {
+                modified,
+                author: Some(user.clone()),
+                base,
+                ..entry
+            },
+        );
+    }
+    fs::write(&path, toml::to_string(&contribution)?)?;
+    debug!("contribution written: {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        let entry = |launch_options: &str, modified: u64| Entry {
+            launch_options: launch_options.to_string(),
+            modified,
+            ..Default::default()
+        };
+        let team = entry("gamemoderun %command%", 2);
+        assert_eq!(
+            status("gamemoderun %command%", Some(&team), None),
+            Status::Same
+        );
+        assert_eq!(status("mangohud %command%", None, None), Status::Missing);
+        assert_eq!(status("", Some(&team), None), Status::Conflict);
+        let synced = entry("", 1);
+        assert_eq!(status("", Some(&team), Some(&synced)), Status::Behind);
+        assert_eq!(
+            status("mangohud %command%", Some(&team), Some(&synced)),
+            Status::Conflict
+        );
+        let synced = entry("gamemoderun %command%", 2);
+        assert_eq!(
+            status("mangohud %command%", Some(&team), Some(&synced)),
+            Status::Ahead
+        );
+    }
+
+    #[test]
+    fn test_read_team_profile() {
+        let team = read_team_profile("./tests/team").unwrap();
+        assert_eq!(
+            team.apps.get("9999999").unwrap().launch_options,
+            "gamescope -W 1920 -H 1080 -- %command%"
+        );
+        assert_eq!(read_contributions("./tests/team").unwrap().len(), 0);
+    }
+}
@@ -0,0 +1,18 @@
+pub mod accounts;
+pub mod app_history;
+pub mod command_preview;
+pub mod compatdata;
+pub mod completion;
+pub mod dxvk;
+pub mod gamescope;
+pub mod hooks;
+pub mod logs;
+pub mod mangohud;
+pub mod notes;
+pub mod palette;
+pub mod profile_history;
+pub mod profiles;
+pub mod protondb;
+pub mod shadercache;
+pub mod team;
+pub mod wrapper;
@@ -0,0 +1,208 @@
+use crate::App;
+use crate::userdata::{self, Account};
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use log::{debug, warn};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Default)]
+pub struct AccountsWindow {
+    pub open: bool,
+    accounts: Vec<Account>,
+    source: usize,
+    targets: BTreeSet<u32>,
+    only_differences: bool,
+    status: String,
+}
+
+impl AccountsWindow {
+    pub fn load(&mut self, localconfig_vdf_path: &String) {
+        match userdata::read_accounts(localconfig_vdf_path) {
+            Ok(accounts) => {
+                debug!("accounts: {:?}", accounts);
+                self.source = accounts
+                    .iter()
+                    .position(|account| &account.localconfig_vdf_path == localconfig_vdf_path)
+                    .unwrap_or_default();
+                self.targets.clear();
+                self.accounts = accounts;
+                self.status.clear();
+            }
+            Err(err) => {
+                warn!("error reading accounts: {}", err);
+                self.accounts.clear();
+                self.status = format!("Error reading accounts: {}", err);
+            }
+        }
+        self.open = true;
+    }
+
+    /// Copies launch options from the source account to every selected target account.
+    /// Returns the launch options that were written to the currently opened account.
+    fn copy(&mut self, appids: &[u32], localconfig_vdf_path: &String) -> BTreeMap<u32, String> {
+        let mut result = BTreeMap::new();
+        let mut copied = 0;
+        for i in 0..self.accounts.len() {
+            if i == self.source || !self.targets.contains(&self.accounts[i].id) {
+                continue;
+            }
+            let (source, target) = if i < self.source {
+                let (left, right) = self.accounts.split_at_mut(self.source);
+                (&right[0], &mut left[i])
+            } else {
+                let (left, right) = self.accounts.split_at_mut(i);
+                (&left[self.source], &mut right[0])
+            };
+            match userdata::copy_launch_options(source, target, appids) {
+                Ok(launch_options) => {
+                    copied += 1;
+                    if &target.localconfig_vdf_path == localconfig_vdf_path {
+                        result = launch_options;
+                    }
+                }
+                Err(err) => {
+                    warn!("error copying launch options to {}: {}", target.id, err);
+                    self.status = format!("Error copying to {}: {}", target.id, err);
+                    return result;
+                }
+            }
+        }
+        self.status = format!("Copied {} app(s) to {} account(s)", appids.len(), copied);
+        result
+    }
+
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        apps: &BTreeMap<u32, App>,
+        localconfig_vdf_path: &String,
+    ) -> BTreeMap<u32, String> {
+        let mut result = BTreeMap::new();
+        let mut open = self.open;
+        egui::Window::new("👥 Accounts")
+            .open(&mut open)
+            .default_size([800.0, 400.0])
+            .show(ctx, |ui| {
+                if self.accounts.is_empty() {
+                    ui.label("No accounts found");
+                    return;
+                }
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("Copy from:");
+                    egui::ComboBox::from_id_salt("AccountSource")
+                        .selected_text(self.accounts[self.source].id.to_string())
+                        .show_ui(ui, |ui| {
+                            for (i, account) in self.accounts.iter().enumerate() {
+                                ui.selectable_value(&mut self.source, i, account.id.to_string());
+                            }
+                        });
+                    let source = self.accounts[self.source].id;
+                    self.targets.remove(&source);
+
+                    ui.label("to:");
+                    for account in self.accounts.iter() {
+                        if account.id == source {
+                            continue;
+                        }
+                        let mut selected = self.targets.contains(&account.id);
+                        if ui.checkbox(&mut selected, account.id.to_string()).changed() {
+                            if selected {
+                                self.targets.insert(account.id);
+                            } else {
+                                self.targets.remove(&account.id);
+                            }
+                        }
+                    }
+
+                    ui.checkbox(&mut self.only_differences, "Only differences");
+                });
+
+                let appids: Vec<u32> = apps
+                    .keys()
+                    .filter(|appid| {
+                        !self.only_differences || userdata::differs(&self.accounts, appid)
+                    })
+                    .copied()
+                    .collect();
+
+                ui.horizontal_wrapped(|ui| {
+                    let differing: Vec<u32> = appids
+                        .iter()
+                        .filter(|appid| userdata::differs(&self.accounts, appid))
+                        .copied()
+                        .collect();
+                    if ui
+                        .add_enabled(
+                            !self.targets.is_empty() && !differing.is_empty(),
+                            egui::Button::new("➡ Copy all differences"),
+                        )
+                        .clicked()
+                    {
+                        result.append(&mut self.copy(&differing, localconfig_vdf_path));
+                    }
+                    ui.label(&self.status);
+                });
+
+                ui.separator();
+
+                let mut copy_appid = None;
+                TableBuilder::new(ui)
+                    .resizable(true)
+                    .striped(true)
+                    .column(Column::initial(200.0).clip(true))
+                    .columns(
+                        Column::initial(200.0).at_least(50.0).clip(true),
+                        self.accounts.len(),
+                    )
+                    .column(Column::remainder().at_least(60.0))
+                    .header(20.0, |mut header| {
+                        header.col(|ui| {
+                            ui.strong(" Steam Apps");
+                        });
+                        for account in self.accounts.iter() {
+                            header.col(|ui| {
+                                ui.strong(account.id.to_string());
+                            });
+                        }
+                        header.col(|_| {});
+                    })
+                    .body(|body| {
+                        body.rows(20.0, appids.len(), |mut row| {
+                            let appid = appids[row.index()];
+                            let differs = userdata::differs(&self.accounts, &appid);
+                            row.col(|ui| {
+                                let name = apps.get(&appid).map_or("", |app| app.name.as_str());
+                                if differs {
+                                    ui.colored_label(ui.visuals().warn_fg_color, name);
+                                } else {
+                                    ui.label(name);
+                                }
+                            });
+                            for account in self.accounts.iter() {
+                                row.col(|ui| {
+                                    let launch_options = account.get(&appid);
+                                    ui.monospace(launch_options).on_hover_text(launch_options);
+                                });
+                            }
+                            row.col(|ui| {
+                                if ui
+                                    .add_enabled(
+                                        differs && !self.targets.is_empty(),
+                                        egui::Button::new("➡ Copy"),
+                                    )
+                                    .clicked()
+                                {
+                                    copy_appid = Some(appid);
+                                }
+                            });
+                        });
+                    });
+                if let Some(appid) = copy_appid {
+                    result.append(&mut self.copy(&[appid], localconfig_vdf_path));
+                }
+            });
+        self.open = open;
+        result
+    }
+}
@@ -0,0 +1,91 @@
+use crate::app_history::{self, Change, Entry};
+use crate::fsutil;
+use eframe::egui;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[derive(Default)]
+pub struct AppHistoryWindow {
+    pub open: bool,
+    appid: u32,
+    name: String,
+    /// Oldest first
+    entries: Vec<Entry>,
+}
+
+impl AppHistoryWindow {
+    pub fn appid(&self) -> u32 {
+        self.appid
+    }
+
+    pub fn load(&mut self, account: &str, appid: u32, name: &str) {
+        self.appid = appid;
+        self.name = name.to_string();
+        self.entries = app_history::load().entries(account, appid).to_vec();
+        self.open = true;
+    }
+
+    /// Returns the app and the launch options to revert to.
+    pub fn show(&mut self, ctx: &egui::Context, current: &str) -> Option<(u32, String)> {
+        let mut result = None;
+        let mut open = self.open;
+        egui::Window::new(format!("🕓 Timeline: {}", self.name))
+            .id(egui::Id::new("AppHistory"))
+            .open(&mut open)
+            .default_size([600.0, 300.0])
+            .show(ctx, |ui| {
+                if self.entries.is_empty() {
+                    ui.label("No launch options recorded yet");
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("AppHistoryGrid")
+                        .striped(true)
+                        .num_columns(4)
+                        .show(ui, |ui| {
+                            for (i, entry) in self.entries.iter().enumerate().rev() {
+                                let previous = i
+                                    .checked_sub(1)
+                                    .map_or("", |i| self.entries[i].launch_options.as_str());
+                                ui.label(fsutil::format_time(
+                                    UNIX_EPOCH + Duration::from_secs(entry.time),
+                                ));
+                                ui.label(entry.source.to_string());
+                                ui.horizontal_wrapped(|ui| {
+                                    ui.spacing_mut().item_spacing.x = 4.0;
+                                    if entry.launch_options.is_empty() {
+                                        ui.weak("(empty)");
+                                    }
+                                    for (change, word) in
+                                        app_history::diff(previous, &entry.launch_options)
+                                    {
+                                        let text = egui::RichText::new(word).monospace();
+                                        match change {
+                                            Change::Same => ui.label(text),
+                                            Change::Removed => ui.label(
+                                                text.strikethrough()
+                                                    .color(ui.visuals().error_fg_color),
+                                            ),
+                                            Change::Added => ui.label(
+                                                text.color(egui::Color32::from_rgb(80, 180, 80)),
+                                            ),
+                                        };
+                                    }
+                                });
+                                if ui
+                                    .add_enabled(
+                                        entry.launch_options != current,
+                                        egui::Button::new("↩ Revert"),
+                                    )
+                                    .on_hover_text("Only this app, unsaved until Save")
+                                    .clicked()
+                                {
+                                    result = Some((self.appid, entry.launch_options.clone()));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        self.open = open;
+        result
+    }
+}
@@ -0,0 +1,156 @@
+use crate::App;
+use crate::appinfo_vdf::{self, LaunchEntry};
+use crate::config_vdf;
+use crate::launch_options;
+use eframe::egui;
+use log::{debug, warn};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The command Steam substitutes for `%command%`, resolved once per selected app.
+#[derive(Default)]
+pub struct CommandPreview {
+    appid: Option<u32>,
+    game: Vec<String>,
+    compat_tool: Option<String>,
+    notes: Vec<String>,
+}
+
+/// Finds a compatibility tool installed by the user or one of the Proton versions from Steam.
+fn proton_dir(steam_dir: &Path, libraries: &[&PathBuf], name: &str) -> PathBuf {
+    let custom = steam_dir.join("compatibilitytools.d").join(name);
+    if custom.is_dir() {
+        return custom;
+    }
+    let official = match name {
+        "proton_experimental" => "Proton - Experimental".to_string(),
+        "proton_hotfix" => "Proton Hotfix".to_string(),
+        _ => match name.strip_prefix("proton_") {
+            Some(version) if version.chars().all(|c| c.is_ascii_digit()) => {
+                format!("Proton {}.0", version)
+            }
+            _ => name.to_string(),
+        },
+    };
+    libraries
+        .iter()
+        .map(|library| library.join("steamapps/common").join(&official))
+        .find(|dir| dir.is_dir())
+        .unwrap_or(steam_dir.join("steamapps/common").join(official))
+}
+
+impl CommandPreview {
+    fn load(&mut self, appid: u32, app: &App, apps: &BTreeMap<u32, App>, steam_dir: &Path) {
+        self.appid = Some(appid);
+        self.notes.clear();
+
+        let entries = match appinfo_vdf::read_launch_entries(
+            &steam_dir.join("appcache/appinfo.vdf"),
+            appid,
+        ) {
+            Ok(entries) => entries,
+            Err(err) => {
+                warn!("error reading appinfo.vdf: {}", err);
+                self.notes
+                    .push(format!("appinfo.vdf could not be read: {}", err));
+                vec![]
+            }
+        };
+        debug!("launch entries: {:?}", entries);
+        let compat_tools =
+            config_vdf::read_compat_tools(steam_dir.join("config/config.vdf")).unwrap_or_default();
+
+        // The default tool only applies to games without a native build
+        let native = entries.iter().find(|entry| !entry.is_windows());
+        self.compat_tool = match (compat_tools.get(&appid), native) {
+            (Some(name), _) => Some(name.clone()),
+            (None, None) => compat_tools.get(&0).cloned(),
+            (None, Some(_)) => None,
+        };
+        let entry: Option<&LaunchEntry> = if self.compat_tool.is_some() {
+            entries
+                .iter()
+                .find(|entry| entry.is_windows())
+                .or(entries.first())
+        } else {
+            native.or(entries.first())
+        };
+
+        let install_dir = app.library.join("steamapps/common").join(&app.installdir);
+        let mut game = vec![];
+        if let Some(name) = &self.compat_tool {
+            let mut libraries: Vec<&PathBuf> = apps.values().map(|app| &app.library).collect();
+            libraries.sort();
+            libraries.dedup();
+            let proton = proton_dir(steam_dir, &libraries, name).join("proton");
+            if !proton.is_file() {
+                self.notes
+                    .push(format!("{} is not installed where expected", name));
+            }
+            game.push(proton.to_string_lossy().to_string());
+            game.push("waitforexitandrun".to_string());
+        }
+        match entry {
+            Some(entry) => {
+                game.push(
+                    install_dir
+                        .join(&entry.executable)
+                        .to_string_lossy()
+                        .to_string(),
+                );
+                game.extend(launch_options::split(&entry.arguments));
+            }
+            None => {
+                self.notes
+                    .push("No launch entry found, showing the install directory".to_string());
+                game.push(install_dir.to_string_lossy().to_string());
+            }
+        }
+        self.game = game;
+    }
+
+    /// Shows the command line of the selected app in a bottom panel, returns false when closed.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        appid: u32,
+        apps: &BTreeMap<u32, App>,
+        launch_options: &str,
+        steam_dir: &Path,
+    ) -> bool {
+        let Some(app) = apps.get(&appid) else {
+            return true;
+        };
+        if self.appid != Some(appid) {
+            self.load(appid, app, apps, steam_dir);
+        }
+        let mut open = true;
+        egui::TopBottomPanel::bottom("CommandPreview")
+            .resizable(true)
+            .show(ctx, |ui| {
+                let command_line = launch_options::command_line(launch_options, &self.game);
+                ui.horizontal_wrapped(|ui| {
+                    if ui.small_button("✖").clicked() {
+                        open = false;
+                    }
+                    ui.strong(format!("⌘ {}", app.name));
+                    if let Some(compat_tool) = &self.compat_tool {
+                        ui.label(format!("({})", compat_tool));
+                    }
+                    if ui.small_button("🗐 Copy").clicked() {
+                        ui.ctx().copy_text(command_line.clone());
+                    }
+                });
+                ui.add(
+                    egui::TextEdit::multiline(&mut command_line.as_str())
+                        .code_editor()
+                        .desired_rows(2)
+                        .desired_width(f32::INFINITY),
+                );
+                for note in self.notes.iter() {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", note));
+                }
+            });
+        open
+    }
+}
@@ -0,0 +1,258 @@
+use crate::compatdata::{self, Prefix};
+use crate::fsutil;
+use eframe::egui;
+use log::warn;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// The prefix of one app, or every orphaned prefix. Sizes and backups are computed in the
+/// background since prefixes hold thousands of files.
+#[derive(Default)]
+pub struct CompatdataWindow {
+    pub open: bool,
+    title: String,
+    prefixes: Vec<Prefix>,
+    names: BTreeMap<u32, String>,
+    loading: Option<Receiver<Vec<Prefix>>>,
+    backup: Option<Receiver<Result<PathBuf, String>>>,
+    /// The prefix being deleted and the outcome
+    delete: Option<Receiver<(PathBuf, Result<(), String>)>>,
+    confirm_delete: Option<Prefix>,
+    empty: String,
+    status: String,
+}
+
+impl CompatdataWindow {
+    fn spawn(&mut self, ctx: &egui::Context, load: impl FnOnce() -> Vec<Prefix> + Send + 'static) {
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ = sender.send(load());
+            ctx.request_repaint();
+        });
+        self.prefixes.clear();
+        self.loading = Some(receiver);
+        self.confirm_delete = None;
+        self.status.clear();
+        self.open = true;
+    }
+
+    pub fn load_app(&mut self, ctx: &egui::Context, appid: u32, name: &str, library: PathBuf) {
+        self.title = name.to_string();
+        self.names = BTreeMap::from([(appid, name.to_string())]);
+        self.empty = "No prefix, the app has not been run with Proton".to_string();
+        self.spawn(ctx, move || {
+            let path = compatdata::prefix_dir(&library, appid);
+            if path.is_dir() {
+                vec![compatdata::inspect(&path, appid)]
+            } else {
+                vec![]
+            }
+        });
+    }
+
+    /// Lists the prefixes in the libraries that belong to none of the installed apps.
+    pub fn load_orphans(
+        &mut self,
+        ctx: &egui::Context,
+        libraries: Vec<PathBuf>,
+        installed: BTreeSet<(u32, PathBuf)>,
+    ) {
+        self.title = "Orphaned prefixes".to_string();
+        self.names.clear();
+        self.empty = "No orphaned prefixes".to_string();
+        self.spawn(ctx, move || compatdata::orphans(&libraries, &installed));
+    }
+
+    fn start_backup(&mut self, ctx: &egui::Context, prefix: &Prefix) {
+        let Some(tarball) = rfd::FileDialog::new()
+            .set_file_name(format!("compatdata-{}.tar.gz", prefix.appid))
+            .save_file()
+        else {
+            return;
+        };
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        let path = prefix.path.clone();
+        thread::spawn(move || {
+            let result = compatdata::backup(&path, &tarball)
+                .map(|_| tarball)
+                .map_err(|err| err.to_string());
+            let _ = sender.send(result);
+            ctx.request_repaint();
+        });
+        self.backup = Some(receiver);
+        self.status = format!("Backing up {}…", prefix.path.display());
+    }
+
+    fn poll(&mut self) {
+        if let Some(receiver) = &self.loading
+            && let Ok(prefixes) = receiver.try_recv()
+        {
+            self.prefixes = prefixes;
+            self.loading = None;
+        }
+        if let Some(receiver) = &self.backup
+            && let Ok(result) = receiver.try_recv()
+        {
+            self.status = match result {
+                Ok(tarball) => format!("Backed up to {}", tarball.display()),
+                Err(err) => {
+                    warn!("error backing up prefix: {}", err);
+                    format!("Backup failed: {}", err)
+                }
+            };
+            self.backup = None;
+        }
+        if let Some(receiver) = &self.delete
+            && let Ok((path, result)) = receiver.try_recv()
+        {
+            self.status = match result {
+                Ok(()) => {
+                    self.prefixes.retain(|other| other.path != path);
+                    format!("Deleted {}", path.display())
+                }
+                Err(err) => {
+                    warn!("error deleting prefix: {}", err);
+                    format!("Delete failed: {}", err)
+                }
+            };
+            self.delete = None;
+        }
+    }
+
+    fn start_delete(&mut self, ctx: &egui::Context, prefix: &Prefix) {
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        let path = prefix.path.clone();
+        thread::spawn(move || {
+            let result = fs::remove_dir_all(&path).map_err(|err| err.to_string());
+            let _ = sender.send((path, result));
+            ctx.request_repaint();
+        });
+        self.delete = Some(receiver);
+        self.status = format!("Deleting {}…", prefix.path.display());
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        self.poll();
+        let mut open = self.open;
+        let mut backup = None;
+        egui::Window::new(format!("🍷 Prefix: {}", self.title))
+            .id(egui::Id::new("Compatdata"))
+            .open(&mut open)
+            .default_size([500.0, 300.0])
+            .show(ctx, |ui| {
+                if self.loading.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Measuring prefixes…");
+                    });
+                    return;
+                }
+                if self.prefixes.is_empty() {
+                    ui.label(&self.empty);
+                }
+                let total: u64 = self.prefixes.iter().map(|prefix| prefix.size).sum();
+                if self.prefixes.len() > 1 {
+                    ui.label(format!(
+                        "{} prefixes, {}",
+                        self.prefixes.len(),
+                        fsutil::format_size(total)
+                    ));
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for prefix in self.prefixes.iter() {
+                        egui::Grid::new(("Prefix", prefix.appid))
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                ui.label("App ID:");
+                                match self.names.get(&prefix.appid) {
+                                    Some(name) => ui.label(format!("{} ({})", prefix.appid, name)),
+                                    None => ui.label(prefix.appid.to_string()),
+                                };
+                                ui.end_row();
+                                ui.label("Location:");
+                                ui.monospace(prefix.path.to_string_lossy());
+                                ui.end_row();
+                                ui.label("Size:");
+                                ui.label(fsutil::format_size(prefix.size));
+                                ui.end_row();
+                                ui.label("Proton:");
+                                ui.label(prefix.version.as_deref().unwrap_or("unknown"));
+                                ui.end_row();
+                                ui.label("Last used:");
+                                ui.label(
+                                    prefix
+                                        .last_used
+                                        .map(fsutil::format_time)
+                                        .unwrap_or("unknown".to_string()),
+                                );
+                                ui.end_row();
+                            });
+                        ui.horizontal(|ui| {
+                            if ui.button("🗁 Open folder").clicked() {
+                                ui.ctx()
+                                    .open_url(egui::OpenUrl::new_tab(compatdata::file_url(
+                                        &prefix.path,
+                                    )));
+                            }
+                            if ui
+                                .add_enabled(
+                                    self.backup.is_none() && self.delete.is_none(),
+                                    egui::Button::new("📦 Back up…"),
+                                )
+                                .clicked()
+                            {
+                                backup = Some(prefix.clone());
+                            }
+                            if ui
+                                .add_enabled(
+                                    self.delete.is_none() && self.backup.is_none(),
+                                    egui::Button::new("🗑 Delete…"),
+                                )
+                                .clicked()
+                            {
+                                self.confirm_delete = Some(prefix.clone());
+                            }
+                        });
+                        ui.separator();
+                    }
+                });
+                if !self.status.is_empty() {
+                    ui.label(&self.status);
+                }
+            });
+        if let Some(prefix) = backup {
+            self.start_backup(ctx, &prefix);
+        }
+
+        if let Some(prefix) = self.confirm_delete.clone() {
+            let modal = egui::Modal::new(egui::Id::new("confirm_delete_prefix")).show(ctx, |ui| {
+                ui.heading("Delete prefix");
+                ui.label(format!(
+                    "Delete {} ({})? Saves and settings kept in the prefix are lost.",
+                    prefix.path.display(),
+                    fsutil::format_size(prefix.size)
+                ));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("🗑 Delete").clicked() {
+                        self.start_delete(ctx, &prefix);
+                        self.confirm_delete = None;
+                    }
+                    if ui.button("✖ Cancel").clicked() {
+                        self.confirm_delete = None;
+                    }
+                });
+            });
+            if modal.should_close() {
+                self.confirm_delete = None;
+            }
+        }
+        self.open = open;
+    }
+}
@@ -0,0 +1,92 @@
+use crate::completion::{self, Completions};
+use crate::env_catalog;
+use crate::launch_options;
+use eframe::egui;
+use egui::text::{CCursor, CCursorRange};
+
+/// Completions for the cursor position of a focused launch options editor.
+pub fn completions(
+    ctx: &egui::Context,
+    id: egui::Id,
+    text: &str,
+    words: &completion::Words,
+) -> Option<Completions> {
+    let range = egui::TextEdit::load_state(ctx, id)?.cursor.char_range()?;
+    if range.primary != range.secondary {
+        return None;
+    }
+    completion::complete(
+        text,
+        range.primary.index,
+        completion::installed_wrappers(),
+        words,
+    )
+}
+
+/// Replaces the completed word and moves the cursor behind it.
+pub fn accept(
+    ctx: &egui::Context,
+    id: egui::Id,
+    text: &mut String,
+    completions: &Completions,
+    index: usize,
+) {
+    let Some((replacement, _)) = completions.items.get(index) else {
+        return;
+    };
+    let (completed, cursor) = completion::insert(text, &completions.range, replacement);
+    *text = completed;
+    if let Some(mut state) = egui::TextEdit::load_state(ctx, id) {
+        state
+            .cursor
+            .set_char_range(Some(CCursorRange::one(CCursor::new(cursor))));
+        state.store(ctx, id);
+    }
+}
+
+/// Lists the completions below the editor with the selected one highlighted, returns the one
+/// that was clicked.
+pub fn show(
+    ctx: &egui::Context,
+    response: &egui::Response,
+    completions: &Completions,
+    selected: usize,
+) -> Option<usize> {
+    let mut result = None;
+    egui::Area::new(response.id.with("completions"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(response.rect.left_bottom())
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                for (i, (replacement, description)) in completions.items.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let item = ui.selectable_label(
+                            i == selected,
+                            egui::RichText::new(replacement).monospace(),
+                        );
+                        if i == selected {
+                            item.scroll_to_me(None);
+                        }
+                        ui.weak(*description);
+                        // The editor loses the focus on press, so the press selects
+                        if item.hovered() && ui.input(|i| i.pointer.primary_pressed()) {
+                            result = Some(i);
+                        }
+                    });
+                }
+                ui.weak("↑↓ choose, Tab or Enter inserts");
+            });
+        });
+    result
+}
+
+/// Descriptions of the known variables in the launch options.
+pub fn env_help(text: &str) -> Option<String> {
+    let help: Vec<String> = launch_options::parse(text)
+        .env
+        .iter()
+        .filter_map(|(name, _)| env_catalog::get(name))
+        .map(|variable| variable.help())
+        .collect();
+    (!help.is_empty()).then(|| help.join("\n\n"))
+}
@@ -0,0 +1,258 @@
+use crate::appinfo_vdf;
+use crate::dxvk::{self, DxvkConfig};
+use eframe::egui;
+use log::warn;
+use std::path::{Path, PathBuf};
+
+const VALUES: [(&str, &str, &str); 8] = [
+    ("dxgi.maxFrameRate", "FPS limit (D3D10/11)", "0 for none"),
+    ("d3d9.maxFrameRate", "FPS limit (D3D9)", "0 for none"),
+    (
+        "dxgi.syncInterval",
+        "Sync interval",
+        "-1 for the game's, 0 for no vsync",
+    ),
+    ("dxgi.maxFrameLatency", "Max frame latency", "1 to 16"),
+    (
+        "d3d11.samplerAnisotropy",
+        "Anisotropic filtering",
+        "0 to 16",
+    ),
+    (
+        "dxvk.numCompilerThreads",
+        "Compiler threads",
+        "0 for all cores",
+    ),
+    ("dxgi.customVendorId", "Vendor ID", "10de, 1002, 8086"),
+    ("dxvk.hud", "HUD", "fps,memory,compiler"),
+];
+
+/// Options that are `True`, `False` or `Auto`.
+const SWITCHES: [(&str, &str); 3] = [
+    ("dxgi.tearFree", "Tear-free present"),
+    ("dxgi.hideNvidiaGpu", "Hide NVIDIA GPU"),
+    (
+        "dxvk.enableGraphicsPipelineLibrary",
+        "Graphics pipeline library",
+    ),
+];
+
+const VKD3D_OPTIONS: [(&str, &str); 6] = [
+    ("dxr", "Ray tracing (DXR)"),
+    ("dxr11", "DXR 1.1"),
+    ("force_static_cbv", "Static CBVs, faster on NVIDIA"),
+    ("single_queue", "Single queue"),
+    ("no_upload_hvv", "No upload heap in VRAM"),
+    ("force_host_cached", "Host cached memory"),
+];
+
+fn is_known(key: &str) -> bool {
+    VALUES.iter().any(|(name, _, _)| *name == key) || SWITCHES.iter().any(|(name, _)| *name == key)
+}
+
+#[derive(Default)]
+pub struct DxvkWindow {
+    pub open: bool,
+    appid: u32,
+    name: String,
+    config: DxvkConfig,
+    /// Entries without a control above, one per line
+    other: String,
+    vkd3d: Vec<String>,
+    /// Config files DXVK would read for the app
+    detected: Vec<PathBuf>,
+    source: Option<PathBuf>,
+    error: Option<String>,
+}
+
+impl DxvkWindow {
+    pub fn load(
+        &mut self,
+        appid: u32,
+        name: &str,
+        launch_options: &str,
+        install_dir: &Path,
+        steam_dir: &Path,
+    ) {
+        self.appid = appid;
+        self.name = name.to_string();
+        self.error = None;
+        self.vkd3d = dxvk::vkd3d_options(launch_options);
+
+        let executables: Vec<String> =
+            appinfo_vdf::read_launch_entries(&steam_dir.join("appcache/appinfo.vdf"), appid)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|entry| entry.executable)
+                .collect();
+        self.detected = dxvk::detect(install_dir, launch_options, &executables);
+        let managed = dxvk::managed_path(appid).ok();
+        let source = managed
+            .filter(|path| path.is_file())
+            .or(self.detected.first().cloned());
+        self.read(source);
+        self.open = true;
+    }
+
+    fn read(&mut self, source: Option<PathBuf>) {
+        self.config = match &source {
+            Some(path) => dxvk::read(path).unwrap_or_else(|err| {
+                warn!("error reading {}: {}", path.display(), err);
+                self.error = Some(format!("{}: {}", path.display(), err));
+                DxvkConfig::default()
+            }),
+            None => DxvkConfig::default(),
+        };
+        self.other = DxvkConfig {
+            entries: self
+                .config
+                .entries
+                .iter()
+                .filter(|(key, _)| !is_known(key))
+                .cloned()
+                .collect(),
+        }
+        .to_string();
+        self.source = source;
+    }
+
+    /// Writes the config file, or removes it and returns `Ok(None)` when nothing is set.
+    fn save(&mut self) -> Result<Option<PathBuf>, ()> {
+        let mut config = self.config.clone();
+        config.entries.retain(|(key, _)| is_known(key));
+        config.entries.extend(dxvk::parse(&self.other).entries);
+        dxvk::managed_path(self.appid)
+            .and_then(|path| Ok(dxvk::write(&path, &config)?.then_some(path)))
+            .map_err(|err| {
+                warn!("error writing dxvk config: {}", err);
+                self.error = Some(err.to_string());
+            })
+    }
+
+    /// Returns the app with its written config file, `None` when it has none, and its
+    /// VKD3D-Proton options.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<(u32, Option<PathBuf>, Vec<String>)> {
+        let mut result = None;
+        let mut open = self.open;
+        egui::Window::new(format!("🧩 DXVK and VKD3D: {}", self.name))
+            .id(egui::Id::new("Dxvk"))
+            .open(&mut open)
+            .default_width(440.0)
+            .show(ctx, |ui| {
+                if let Some(err) = &self.error {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", err));
+                }
+                let mut import = None;
+                for path in self.detected.iter() {
+                    ui.horizontal(|ui| {
+                        let current = self.source.as_ref() == Some(path);
+                        if ui
+                            .add_enabled(!current, egui::Button::new("⮊ Import"))
+                            .clicked()
+                        {
+                            import = Some(path.clone());
+                        }
+                        ui.monospace(path.to_string_lossy());
+                    });
+                }
+                if import.is_some() {
+                    self.read(import);
+                }
+                if let Some(source) = &self.source {
+                    ui.weak(format!("Editing a copy of {}", source.display()));
+                }
+                ui.separator();
+
+                ui.strong("dxvk.conf");
+                egui::Grid::new("DxvkValues").num_columns(2).show(ui, |ui| {
+                    for (key, label, hint) in VALUES.iter() {
+                        ui.label(format!("{}:", label)).on_hover_text(*key);
+                        let mut value = self.config.value(key).unwrap_or_default().to_string();
+                        if ui
+                            .add(egui::TextEdit::singleline(&mut value).hint_text(*hint))
+                            .changed()
+                        {
+                            if value.trim().is_empty() {
+                                self.config.remove(key);
+                            } else {
+                                self.config.set(key, value.trim().to_string());
+                            }
+                        }
+                        ui.end_row();
+                    }
+                    for (key, label) in SWITCHES.iter() {
+                        ui.label(format!("{}:", label)).on_hover_text(*key);
+                        let mut value = self.config.value(key).map(str::to_string);
+                        egui::ComboBox::from_id_salt(key)
+                            .selected_text(value.as_deref().unwrap_or("default"))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut value, None, "default");
+                                for name in ["True", "False", "Auto"] {
+                                    ui.selectable_value(&mut value, Some(name.to_string()), name);
+                                }
+                            });
+                        if value.as_deref() != self.config.value(key) {
+                            match value {
+                                Some(value) => self.config.set(key, value),
+                                None => self.config.remove(key),
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+
+                ui.label("Other settings (key = value per line):");
+                ui.add_sized(
+                    [ui.available_width(), 60.0],
+                    egui::TextEdit::multiline(&mut self.other).code_editor(),
+                );
+                ui.separator();
+
+                ui.strong(dxvk::VKD3D_CONFIG);
+                egui::Grid::new("Vkd3dOptions")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for (i, (option, label)) in VKD3D_OPTIONS.iter().enumerate() {
+                            let mut set = self.vkd3d.iter().any(|other| other == option);
+                            if ui
+                                .checkbox(&mut set, *label)
+                                .on_hover_text(*option)
+                                .changed()
+                            {
+                                if set {
+                                    self.vkd3d.push(option.to_string());
+                                } else {
+                                    self.vkd3d.retain(|other| other != option);
+                                }
+                            }
+                            if i % 2 == 1 {
+                                ui.end_row();
+                            }
+                        }
+                    });
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("💾 Save and use").clicked()
+                        && let Ok(path) = self.save()
+                    {
+                        result = Some((self.appid, path, self.vkd3d.clone()));
+                        self.open = false;
+                    }
+                    if ui
+                        .button("🗑 Remove from launch options")
+                        .on_hover_text("Keeps the config file")
+                        .clicked()
+                    {
+                        result = Some((self.appid, None, vec![]));
+                        self.open = false;
+                    }
+                    if ui.button("✖ Cancel").clicked() {
+                        self.open = false;
+                    }
+                });
+            });
+        self.open = open && self.open;
+        result
+    }
+}
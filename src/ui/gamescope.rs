@@ -0,0 +1,249 @@
+use crate::gamescope::{self, Filter, Gamescope, Scaler};
+use crate::launch_options;
+use eframe::egui;
+
+#[derive(Default)]
+pub struct GamescopeWindow {
+    pub open: bool,
+    /// The apps with their current launch options
+    apps: Vec<(u32, String)>,
+    title: String,
+    enabled: bool,
+    options: Gamescope,
+    extra: String,
+    error: Option<String>,
+    /// Apps whose own gamescope options would be replaced, waiting for confirmation
+    confirm_overwrite: Vec<u32>,
+}
+
+/// A checkbox that sets or unsets a value, followed by the value when set.
+fn optional(ui: &mut egui::Ui, label: &str, value: &mut Option<u32>, default: u32, max: u32) {
+    let mut set = value.is_some();
+    if ui.checkbox(&mut set, label).changed() {
+        *value = set.then_some(default);
+    }
+    if let Some(value) = value {
+        ui.add(egui::DragValue::new(value).range(0..=max));
+    }
+}
+
+fn choice<T: Copy + PartialEq + std::fmt::Display>(
+    ui: &mut egui::Ui,
+    id: &str,
+    value: &mut Option<T>,
+    all: &[T],
+) {
+    let text = value.map_or("default".to_string(), |value| value.to_string());
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(text)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(value, None, "default");
+            for item in all.iter() {
+                ui.selectable_value(value, Some(*item), item.to_string());
+            }
+        });
+}
+
+impl GamescopeWindow {
+    /// Edits the gamescope options of the apps, starting from those of the first app.
+    pub fn load(&mut self, apps: Vec<(u32, String)>, title: String) {
+        let launch_options = apps
+            .first()
+            .map(|(_, launch_options)| launch_options.clone())
+            .unwrap_or_default();
+        self.apps = apps;
+        self.title = title;
+        self.error = None;
+        self.confirm_overwrite.clear();
+        match gamescope::from_launch_options(&launch_options) {
+            Ok(options) => {
+                self.enabled = options.is_some();
+                self.options = options.unwrap_or_default();
+            }
+            Err(err) => {
+                self.error = Some(err);
+                self.enabled = true;
+                self.options = Gamescope::default();
+            }
+        }
+        self.extra = self
+            .options
+            .extra
+            .iter()
+            .map(|word| launch_options::quote(word))
+            .collect::<Vec<String>>()
+            .join(" ");
+        self.open = true;
+    }
+
+    /// The apps other than the first, whose options were loaded, that have different gamescope
+    /// options of their own.
+    fn overwritten(&self, options: Option<&Gamescope>) -> Vec<u32> {
+        self.apps
+            .iter()
+            .skip(1)
+            .filter(
+                |(_, launch_options)| match gamescope::from_launch_options(launch_options) {
+                    Ok(Some(own)) => Some(&own) != options,
+                    Ok(None) => false,
+                    Err(_) => true,
+                },
+            )
+            .map(|(appid, _)| *appid)
+            .collect()
+    }
+
+    fn appids(&self) -> Vec<u32> {
+        self.apps.iter().map(|(appid, _)| *appid).collect()
+    }
+
+    /// Returns the apps with the options to apply, `None` removing gamescope.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<(Vec<u32>, Option<Gamescope>)> {
+        let mut result = None;
+        let mut open = self.open;
+        egui::Window::new(format!("🖥 Gamescope: {}", self.title))
+            .id(egui::Id::new("Gamescope"))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                if let Some(err) = &self.error {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!("⚠ Existing options could not be read: {}", err),
+                    );
+                }
+                ui.checkbox(&mut self.enabled, "Run with gamescope");
+                ui.add_enabled_ui(self.enabled, |ui| {
+                    let options = &mut self.options;
+                    egui::Grid::new("GamescopeGrid")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            ui.label("Output resolution:");
+                            ui.horizontal(|ui| {
+                                optional(ui, "W", &mut options.output_width, 1920, 7680);
+                                optional(ui, "H", &mut options.output_height, 1080, 4320);
+                            });
+                            ui.end_row();
+
+                            ui.label("Game resolution:");
+                            ui.horizontal(|ui| {
+                                optional(ui, "W", &mut options.width, 1280, 7680);
+                                optional(ui, "H", &mut options.height, 720, 4320);
+                            });
+                            ui.end_row();
+
+                            ui.label("Refresh rate:");
+                            optional(ui, "Hz", &mut options.refresh_rate, 60, 1000);
+                            ui.end_row();
+
+                            ui.label("Frame limit:");
+                            optional(ui, "FPS", &mut options.framerate_limit, 60, 1000);
+                            ui.end_row();
+
+                            ui.label("Upscaling filter:");
+                            choice(ui, "GamescopeFilter", &mut options.filter, &Filter::ALL);
+                            ui.end_row();
+
+                            ui.label("Scaler:");
+                            choice(ui, "GamescopeScaler", &mut options.scaler, &Scaler::ALL);
+                            ui.end_row();
+
+                            ui.label("Sharpness:");
+                            optional(ui, "0 – 20", &mut options.sharpness, 2, 20);
+                            ui.end_row();
+
+                            ui.label("Window:");
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut options.fullscreen, "Fullscreen");
+                                ui.checkbox(&mut options.borderless, "Borderless");
+                            });
+                            ui.end_row();
+
+                            ui.label("Extras:");
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut options.hdr, "HDR");
+                                ui.checkbox(&mut options.mangoapp, "MangoHud (mangoapp)");
+                            });
+                            ui.end_row();
+
+                            ui.label("Other flags:");
+                            if ui.text_edit_singleline(&mut self.extra).changed() {
+                                options.extra = launch_options::split(&self.extra);
+                            }
+                            ui.end_row();
+                        });
+                });
+
+                let problems = if self.enabled {
+                    self.options.validate()
+                } else {
+                    vec![]
+                };
+                ui.separator();
+                if self.enabled {
+                    let mut words = vec![gamescope::COMMAND.to_string()];
+                    words.extend(self.options.args());
+                    words.push("--".to_string());
+                    let words: Vec<String> = words
+                        .iter()
+                        .map(|word| launch_options::quote(word))
+                        .collect();
+                    ui.monospace(words.join(" "));
+                } else {
+                    ui.weak("gamescope will be removed from the launch options");
+                }
+                for problem in problems.iter() {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", problem));
+                }
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            problems.is_empty(),
+                            egui::Button::new(format!("✔ Apply to {} app(s)", self.apps.len())),
+                        )
+                        .clicked()
+                    {
+                        let options = self.enabled.then(|| self.options.clone());
+                        self.confirm_overwrite = self.overwritten(options.as_ref());
+                        if self.confirm_overwrite.is_empty() {
+                            result = Some((self.appids(), options));
+                            self.open = false;
+                        }
+                    }
+                    if ui.button("✖ Cancel").clicked() {
+                        self.open = false;
+                    }
+                });
+            });
+
+        if !self.confirm_overwrite.is_empty() {
+            let modal =
+                egui::Modal::new(egui::Id::new("confirm_overwrite_gamescope")).show(ctx, |ui| {
+                    ui.heading("Replace gamescope options");
+                    ui.label(format!(
+                        "{} of the {} apps have gamescope options of their own, which will be \
+                         replaced by these.",
+                        self.confirm_overwrite.len(),
+                        self.apps.len()
+                    ));
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("✔ Replace").clicked() {
+                            result =
+                                Some((self.appids(), self.enabled.then(|| self.options.clone())));
+                            self.confirm_overwrite.clear();
+                            self.open = false;
+                        }
+                        if ui.button("✖ Cancel").clicked() {
+                            self.confirm_overwrite.clear();
+                        }
+                    });
+                });
+            if modal.should_close() {
+                self.confirm_overwrite.clear();
+            }
+        }
+        self.open = open && self.open;
+        result
+    }
+}
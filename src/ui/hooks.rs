@@ -0,0 +1,87 @@
+use crate::hooks::{self, Hooks};
+use eframe::egui;
+use std::fs;
+
+#[derive(Default)]
+pub struct HooksWindow {
+    pub open: bool,
+    appid: u32,
+    name: String,
+    hooks: Hooks,
+    /// The end of the log, read when the window opens
+    log: String,
+}
+
+impl HooksWindow {
+    pub fn load(&mut self, appid: u32, name: &str, hooks: Option<&Hooks>) {
+        self.appid = appid;
+        self.name = name.to_string();
+        self.hooks = hooks.cloned().unwrap_or_default();
+        self.log = hooks::log_path(appid)
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|log| {
+                let lines: Vec<&str> = log.lines().collect();
+                lines[lines.len().saturating_sub(20)..].join("\n")
+            })
+            .unwrap_or_default();
+        self.open = true;
+    }
+
+    /// Returns the app with its edited hooks, empty to remove them.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<(u32, Hooks)> {
+        let mut result = None;
+        let mut open = self.open;
+        egui::Window::new(format!("🔗 Hooks: {}", self.name))
+            .id(egui::Id::new("Hooks"))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label("Run before the game starts:");
+                ui.add_sized(
+                    [ui.available_width(), 60.0],
+                    egui::TextEdit::multiline(&mut self.hooks.pre)
+                        .code_editor()
+                        .hint_text("powerprofilesctl set performance"),
+                );
+                ui.label("Run after the game exits:");
+                ui.add_sized(
+                    [ui.available_width(), 60.0],
+                    egui::TextEdit::multiline(&mut self.hooks.post)
+                        .code_editor()
+                        .hint_text("powerprofilesctl set balanced"),
+                );
+                if let Ok(path) = hooks::log_path(self.appid) {
+                    ui.horizontal(|ui| {
+                        ui.label("Output is logged to");
+                        ui.monospace(path.to_string_lossy());
+                    });
+                }
+                if !self.log.is_empty() {
+                    egui::CollapsingHeader::new("Last output").show(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .max_height(150.0)
+                            .show(ui, |ui| {
+                                ui.monospace(&self.log);
+                            });
+                    });
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("✔ Apply").clicked() {
+                        result = Some((self.appid, self.hooks.clone()));
+                        self.open = false;
+                    }
+                    if ui.button("🗑 Remove").clicked() {
+                        result = Some((self.appid, Hooks::default()));
+                        self.open = false;
+                    }
+                    if ui.button("✖ Cancel").clicked() {
+                        self.open = false;
+                    }
+                });
+            });
+        self.open = open && self.open;
+        result
+    }
+}
@@ -0,0 +1,236 @@
+use crate::hooks::{self, Hooks};
+use crate::logs::{self, Severity};
+use crate::wrapper::{self, Wrapper};
+use directories::BaseDirs;
+use eframe::egui;
+use log::warn;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Only the end of large logs is read, Proton logs grow to hundreds of megabytes.
+const MAX_BYTES: u64 = 4 * 1024 * 1024;
+
+struct LogFile {
+    path: PathBuf,
+    /// Client logs are shared by all apps, so only lines about the app are kept
+    shared: bool,
+}
+
+#[derive(Default)]
+pub struct LogWindow {
+    pub open: bool,
+    appid: u32,
+    name: String,
+    /// The model of the app's wrapper script, whose variables are used in place of the launch
+    /// options
+    wrapper: Option<Wrapper>,
+    hooks: Hooks,
+    files: Vec<LogFile>,
+    current: usize,
+    lines: Vec<(String, Option<Severity>)>,
+    /// Modification time and length of the file when it was read
+    read: Option<(SystemTime, u64)>,
+    errors_only: bool,
+    follow: bool,
+    error: Option<String>,
+}
+
+impl LogWindow {
+    pub fn appid(&self) -> u32 {
+        self.appid
+    }
+
+    pub fn load(
+        &mut self,
+        appid: u32,
+        name: &str,
+        launch_options: &str,
+        hooks: Option<&Hooks>,
+        steam_dir: &Path,
+    ) {
+        self.appid = appid;
+        self.name = name.to_string();
+        self.hooks = hooks.cloned().unwrap_or_default();
+        self.wrapper = if wrapper::is_wrapped(launch_options, appid) {
+            wrapper::read(appid).unwrap_or_else(|err| {
+                warn!("error reading wrapper: {}", err);
+                None
+            })
+        } else {
+            None
+        };
+        let home = BaseDirs::new()
+            .map(|dirs| dirs.home_dir().to_path_buf())
+            .unwrap_or_default();
+        let wrapper_env = self
+            .wrapper
+            .as_ref()
+            .map_or(&[][..], |wrapper| wrapper.env.as_slice());
+        self.files = vec![LogFile {
+            path: logs::proton_log(&home, launch_options, wrapper_env, appid),
+            shared: false,
+        }];
+        self.files.extend(
+            [wrapper::log_path(appid), hooks::log_path(appid)]
+                .into_iter()
+                .flatten()
+                .filter(|path| path.is_file())
+                .map(|path| LogFile {
+                    path,
+                    shared: false,
+                }),
+        );
+        self.files.extend(
+            logs::steam_logs(steam_dir)
+                .into_iter()
+                .map(|path| LogFile { path, shared: true }),
+        );
+        self.current = 0;
+        self.read = None;
+        self.follow = true;
+        self.reload();
+        self.open = true;
+    }
+
+    /// Reads the current file again when it changed.
+    fn reload(&mut self) {
+        let Some(file) = self.files.get(self.current) else {
+            return;
+        };
+        let stamp = std::fs::metadata(&file.path)
+            .ok()
+            .and_then(|metadata| Some((metadata.modified().ok()?, metadata.len())));
+        if stamp.is_some() && stamp == self.read {
+            return;
+        }
+        self.read = stamp;
+        match logs::tail(&file.path, MAX_BYTES) {
+            Ok(lines) => {
+                self.lines = lines
+                    .into_iter()
+                    .filter(|line| !file.shared || logs::mentions(line, self.appid))
+                    .map(|line| {
+                        let severity = logs::severity(&line);
+                        (line, severity)
+                    })
+                    .collect();
+                self.error = None;
+            }
+            Err(err) => {
+                self.lines.clear();
+                self.error = Some(if self.current > 0 {
+                    err.to_string()
+                } else {
+                    format!(
+                        "{}: {}, enable PROTON_LOG and start the game",
+                        file.path.display(),
+                        err
+                    )
+                });
+            }
+        }
+    }
+
+    /// Returns the launch options with `PROTON_LOG` toggled.
+    pub fn show(&mut self, ctx: &egui::Context, launch_options: &str) -> Option<String> {
+        if self.follow {
+            self.reload();
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+        let mut result = None;
+        let mut open = self.open;
+        egui::Window::new(format!("🧾 Logs: {}", self.name))
+            .id(egui::Id::new("Logs"))
+            .open(&mut open)
+            .default_size([700.0, 400.0])
+            .show(ctx, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    let mut enabled = logs::is_proton_log(launch_options)
+                        || self
+                            .wrapper
+                            .as_ref()
+                            .is_some_and(|wrapper| logs::is_proton_log_env(&wrapper.env));
+                    let hover = if self.wrapper.is_some() {
+                        "Written to the wrapper script"
+                    } else {
+                        "Unsaved until the launch options are saved"
+                    };
+                    if ui
+                        .checkbox(&mut enabled, "Enable PROTON_LOG for next launch")
+                        .on_hover_text(hover)
+                        .changed()
+                    {
+                        if let Some(wrapper) = &mut self.wrapper {
+                            logs::set_proton_log_env(&mut wrapper.env, enabled);
+                            if let Err(err) = wrapper::write(self.appid, wrapper, &self.hooks) {
+                                warn!("error writing wrapper: {}", err);
+                                self.error = Some(err.to_string());
+                            }
+                            // A variable before the script would still turn the log on
+                            if !enabled && logs::is_proton_log(launch_options) {
+                                result = Some(logs::set_proton_log(launch_options, false));
+                            }
+                        } else {
+                            result = Some(logs::set_proton_log(launch_options, enabled));
+                        }
+                    }
+                    ui.checkbox(&mut self.errors_only, "Errors only");
+                    ui.checkbox(&mut self.follow, "Follow");
+                    if ui.button("⟳ Reload").clicked() {
+                        self.read = None;
+                        self.reload();
+                    }
+                });
+                ui.horizontal_wrapped(|ui| {
+                    let mut selected = self.current;
+                    for (i, file) in self.files.iter().enumerate() {
+                        let name = file
+                            .path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_default();
+                        ui.selectable_value(&mut selected, i, name)
+                            .on_hover_text(file.path.to_string_lossy());
+                    }
+                    if selected != self.current {
+                        self.current = selected;
+                        self.read = None;
+                        self.reload();
+                    }
+                });
+                if let Some(err) = &self.error {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", err));
+                }
+                let errors = self
+                    .lines
+                    .iter()
+                    .filter(|(_, severity)| *severity == Some(Severity::Error))
+                    .count();
+                ui.label(format!("{} lines, {} errors", self.lines.len(), errors));
+                ui.separator();
+
+                let lines: Vec<&(String, Option<Severity>)> = self
+                    .lines
+                    .iter()
+                    .filter(|(_, severity)| !self.errors_only || *severity == Some(Severity::Error))
+                    .collect();
+                let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                egui::ScrollArea::both()
+                    .auto_shrink(false)
+                    .stick_to_bottom(true)
+                    .show_rows(ui, row_height, lines.len(), |ui, range| {
+                        for (line, severity) in lines[range].iter() {
+                            let text = egui::RichText::new(line).monospace();
+                            let text = match severity {
+                                Some(Severity::Error) => text.color(ui.visuals().error_fg_color),
+                                Some(Severity::Warning) => text.color(ui.visuals().warn_fg_color),
+                                None => text,
+                            };
+                            ui.add(egui::Label::new(text).extend());
+                        }
+                    });
+            });
+        self.open = open;
+        result
+    }
+}
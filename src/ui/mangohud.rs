@@ -0,0 +1,253 @@
+use crate::appinfo_vdf;
+use crate::gamescope;
+use crate::mangohud::{self, MangoHudConfig};
+use eframe::egui;
+use log::warn;
+use std::path::{Path, PathBuf};
+
+/// Options that are turned on by their key alone.
+const TOGGLES: [(&str, &str); 12] = [
+    ("fps", "FPS"),
+    ("frametime", "Frame time"),
+    ("frame_timing", "Frame time graph"),
+    ("cpu_stats", "CPU load"),
+    ("cpu_temp", "CPU temperature"),
+    ("gpu_stats", "GPU load"),
+    ("gpu_temp", "GPU temperature"),
+    ("ram", "RAM"),
+    ("vram", "VRAM"),
+    ("wine", "Wine version"),
+    ("gamemode", "GameMode status"),
+    ("no_display", "Hidden until toggled"),
+];
+
+const VALUES: [(&str, &str); 4] = [
+    ("fps_limit", "FPS limit"),
+    ("font_size", "Font size"),
+    ("background_alpha", "Background alpha"),
+    ("toggle_hud", "Toggle key"),
+];
+
+const POSITIONS: [&str; 6] = [
+    "top-left",
+    "top-center",
+    "top-right",
+    "bottom-left",
+    "bottom-center",
+    "bottom-right",
+];
+
+fn is_known(key: &str) -> bool {
+    key == "position"
+        || TOGGLES.iter().any(|(name, _)| *name == key)
+        || VALUES.iter().any(|(name, _)| *name == key)
+}
+
+#[derive(Default)]
+pub struct MangoHudWindow {
+    pub open: bool,
+    appid: u32,
+    name: String,
+    config: MangoHudConfig,
+    /// Entries without a control above, one per line
+    other: String,
+    /// Config files MangoHud would read for the app
+    detected: Vec<PathBuf>,
+    source: Option<PathBuf>,
+    mangoapp: bool,
+    error: Option<String>,
+}
+
+impl MangoHudWindow {
+    pub fn load(&mut self, appid: u32, name: &str, launch_options: &str, steam_dir: &Path) {
+        self.appid = appid;
+        self.name = name.to_string();
+        self.error = None;
+        self.mangoapp = gamescope::from_launch_options(launch_options)
+            .ok()
+            .flatten()
+            .is_some_and(|options| options.mangoapp);
+
+        let executables: Vec<String> =
+            appinfo_vdf::read_launch_entries(&steam_dir.join("appcache/appinfo.vdf"), appid)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|entry| entry.executable)
+                .collect();
+        self.detected = mangohud::user_dir()
+            .map(|dir| mangohud::detect(&dir, launch_options, &executables))
+            .unwrap_or_default();
+        let managed = mangohud::managed_path(appid).ok();
+        let source = managed
+            .filter(|path| path.is_file())
+            .or(self.detected.first().cloned());
+        self.read(source);
+        self.open = true;
+    }
+
+    fn read(&mut self, source: Option<PathBuf>) {
+        self.config = match &source {
+            Some(path) => mangohud::read(path).unwrap_or_else(|err| {
+                warn!("error reading {}: {}", path.display(), err);
+                self.error = Some(format!("{}: {}", path.display(), err));
+                MangoHudConfig::default()
+            }),
+            None => mangohud::parse("fps\nframe_timing\ncpu_stats\ngpu_stats\n"),
+        };
+        self.other = MangoHudConfig {
+            entries: self
+                .config
+                .entries
+                .iter()
+                .filter(|(key, _)| !is_known(key))
+                .cloned()
+                .collect(),
+        }
+        .to_string();
+        self.source = source;
+    }
+
+    fn save(&mut self) -> Option<PathBuf> {
+        let mut config = self.config.clone();
+        config.entries.retain(|(key, _)| is_known(key));
+        config.entries.extend(mangohud::parse(&self.other).entries);
+        match mangohud::managed_path(self.appid)
+            .and_then(|path| mangohud::write(&path, &config).map(|_| path))
+        {
+            Ok(path) => Some(path),
+            Err(err) => {
+                warn!("error writing mangohud config: {}", err);
+                self.error = Some(err.to_string());
+                None
+            }
+        }
+    }
+
+    /// Returns the app with its written config file, or `None` to stop using MangoHud.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<(u32, Option<PathBuf>)> {
+        let mut result = None;
+        let mut open = self.open;
+        egui::Window::new(format!("📊 MangoHud: {}", self.name))
+            .id(egui::Id::new("MangoHud"))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                if let Some(err) = &self.error {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", err));
+                }
+                if self.mangoapp {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        "⚠ gamescope already shows MangoHud with --mangoapp",
+                    );
+                }
+                let mut import = None;
+                for path in self.detected.iter() {
+                    ui.horizontal(|ui| {
+                        let current = self.source.as_ref() == Some(path);
+                        if ui
+                            .add_enabled(!current, egui::Button::new("⮊ Import"))
+                            .clicked()
+                        {
+                            import = Some(path.clone());
+                        }
+                        ui.monospace(path.to_string_lossy());
+                    });
+                }
+                if import.is_some() {
+                    self.read(import);
+                }
+                if let Some(source) = &self.source {
+                    ui.weak(format!("Editing a copy of {}", source.display()));
+                }
+                ui.separator();
+
+                egui::Grid::new("MangoHudToggles")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for (i, (key, label)) in TOGGLES.iter().enumerate() {
+                            let mut set = self.config.is_set(key);
+                            if ui.checkbox(&mut set, *label).changed() {
+                                if set {
+                                    self.config.set(key, None);
+                                } else {
+                                    self.config.remove(key);
+                                }
+                            }
+                            if i % 3 == 2 {
+                                ui.end_row();
+                            }
+                        }
+                    });
+                ui.separator();
+
+                egui::Grid::new("MangoHudValues")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Position:");
+                        let mut position = self.config.value("position").map(str::to_string);
+                        egui::ComboBox::from_id_salt("MangoHudPosition")
+                            .selected_text(position.as_deref().unwrap_or("default"))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut position, None, "default");
+                                for name in POSITIONS.iter() {
+                                    ui.selectable_value(
+                                        &mut position,
+                                        Some(name.to_string()),
+                                        *name,
+                                    );
+                                }
+                            });
+                        if position.as_deref() != self.config.value("position") {
+                            match position {
+                                Some(position) => self.config.set("position", Some(position)),
+                                None => self.config.remove("position"),
+                            }
+                        }
+                        ui.end_row();
+
+                        for (key, label) in VALUES.iter() {
+                            ui.label(format!("{}:", label));
+                            let mut value = self.config.value(key).unwrap_or_default().to_string();
+                            if ui.text_edit_singleline(&mut value).changed() {
+                                if value.trim().is_empty() {
+                                    self.config.remove(key);
+                                } else {
+                                    self.config.set(key, Some(value.trim().to_string()));
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                ui.label("Other settings (key=value per line):");
+                ui.add_sized(
+                    [ui.available_width(), 60.0],
+                    egui::TextEdit::multiline(&mut self.other).code_editor(),
+                );
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("💾 Save and use").clicked()
+                        && let Some(path) = self.save()
+                    {
+                        result = Some((self.appid, Some(path)));
+                        self.open = false;
+                    }
+                    if ui
+                        .button("🗑 Remove from launch options")
+                        .on_hover_text("Keeps the config file")
+                        .clicked()
+                    {
+                        result = Some((self.appid, None));
+                        self.open = false;
+                    }
+                    if ui.button("✖ Cancel").clicked() {
+                        self.open = false;
+                    }
+                });
+            });
+        self.open = open && self.open;
+        result
+    }
+}
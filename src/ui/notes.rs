@@ -0,0 +1,64 @@
+use crate::profile;
+use eframe::egui;
+
+#[derive(Default)]
+pub struct NotesWindow {
+    pub open: bool,
+    appid: u32,
+    name: String,
+    note: String,
+    tags: String,
+}
+
+impl NotesWindow {
+    pub fn load(
+        &mut self,
+        appid: u32,
+        name: &str,
+        note: Option<&String>,
+        tags: Option<&Vec<String>>,
+    ) {
+        self.appid = appid;
+        self.name = name.to_string();
+        self.note = note.cloned().unwrap_or_default();
+        self.tags = tags.map(|tags| tags.join(", ")).unwrap_or_default();
+        self.open = true;
+    }
+
+    /// Returns the app with its edited note and tags.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<(u32, String, Vec<String>)> {
+        let mut result = None;
+        let mut open = self.open;
+        egui::Window::new(format!("📝 Notes: {}", self.name))
+            .id(egui::Id::new("Notes"))
+            .open(&mut open)
+            .default_size([400.0, 200.0])
+            .show(ctx, |ui| {
+                ui.label("Tags (comma separated):");
+                ui.add_sized(
+                    [ui.available_width(), 20.0],
+                    egui::TextEdit::singleline(&mut self.tags),
+                );
+                ui.label("Notes:");
+                ui.add_sized(
+                    [ui.available_width(), 100.0],
+                    egui::TextEdit::multiline(&mut self.note),
+                );
+                ui.horizontal(|ui| {
+                    if ui.button("✔ Apply").clicked() {
+                        result = Some((
+                            self.appid,
+                            self.note.trim().to_string(),
+                            profile::parse_tags(&self.tags),
+                        ));
+                        self.open = false;
+                    }
+                    if ui.button("✖ Cancel").clicked() {
+                        self.open = false;
+                    }
+                });
+            });
+        self.open = open && self.open;
+        result
+    }
+}
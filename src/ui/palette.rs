@@ -0,0 +1,95 @@
+use crate::fuzzy;
+use eframe::egui;
+
+#[derive(Default)]
+pub struct CommandPalette {
+    pub open: bool,
+    text: String,
+    selected: usize,
+}
+
+impl CommandPalette {
+    pub fn load(&mut self) {
+        self.text.clear();
+        self.selected = 0;
+        self.open = true;
+    }
+
+    /// Lists the commands matching the typed text, each as a label and a shortcut.
+    /// Returns the index of the chosen command.
+    pub fn show(&mut self, ctx: &egui::Context, commands: &[(String, String)]) -> Option<usize> {
+        let mut matches: Vec<(i64, usize)> = commands
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (label, _))| {
+                fuzzy::find(&self.text, label).map(|found| (found.score, i))
+            })
+            .collect();
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if down && self.selected + 1 < matches.len() {
+            self.selected += 1;
+        }
+        let mut result = None;
+        if enter {
+            result = matches.get(self.selected).map(|(_, i)| *i);
+        }
+
+        egui::Window::new("Command palette")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 40.0])
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.text)
+                        .hint_text("Type a command…")
+                        .desired_width(f32::INFINITY),
+                );
+                response.request_focus();
+                if response.changed() {
+                    self.selected = 0;
+                }
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for (row, (_, i)) in matches.iter().enumerate() {
+                            let (label, shortcut) = &commands[*i];
+                            ui.horizontal(|ui| {
+                                let response = ui.selectable_label(row == self.selected, label);
+                                if row == self.selected && (up || down) {
+                                    response.scroll_to_me(None);
+                                }
+                                if response.clicked() {
+                                    result = Some(*i);
+                                }
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        ui.weak(shortcut);
+                                    },
+                                );
+                            });
+                        }
+                    });
+            });
+        if escape || result.is_some() {
+            self.open = false;
+        }
+        result
+    }
+}
@@ -0,0 +1,70 @@
+use crate::profile_history::{self, Revision};
+use eframe::egui;
+use log::warn;
+
+#[derive(Default)]
+pub struct ProfileHistoryWindow {
+    pub open: bool,
+    appid: u32,
+    name: String,
+    revisions: Vec<Revision>,
+    status: String,
+}
+
+impl ProfileHistoryWindow {
+    pub fn load(&mut self, profile: &str, appid: u32, name: &str) {
+        self.appid = appid;
+        self.name = name.to_string();
+        let revisions = profile_history::repository_dir()
+            .and_then(|dir| profile_history::app_history(&dir, profile, appid));
+        match revisions {
+            Ok(revisions) => {
+                self.status = if revisions.is_empty() {
+                    "No history saved yet".to_string()
+                } else {
+                    String::new()
+                };
+                self.revisions = revisions;
+            }
+            Err(err) => {
+                warn!("error reading profile history: {}", err);
+                self.revisions.clear();
+                self.status = format!("Error reading history: {}", err);
+            }
+        }
+        self.open = true;
+    }
+
+    /// Returns the app and the launch options to revert to.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<(u32, String)> {
+        let mut result = None;
+        let mut open = self.open;
+        egui::Window::new(format!("🕘 History: {}", self.name))
+            .id(egui::Id::new("ProfileHistory"))
+            .open(&mut open)
+            .default_size([600.0, 300.0])
+            .show(ctx, |ui| {
+                if !self.status.is_empty() {
+                    ui.label(&self.status);
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("ProfileHistoryGrid")
+                        .striped(true)
+                        .num_columns(4)
+                        .show(ui, |ui| {
+                            for revision in self.revisions.iter() {
+                                ui.monospace(&revision.id);
+                                ui.label(&revision.time);
+                                ui.monospace(&revision.launch_options);
+                                if ui.button("↩ Revert").clicked() {
+                                    result = Some((self.appid, revision.launch_options.clone()));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        self.open = open;
+        result
+    }
+}
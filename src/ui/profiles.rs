@@ -0,0 +1,123 @@
+use crate::profile::{self, Profile};
+use eframe::egui;
+use log::warn;
+
+#[derive(PartialEq)]
+enum Edit {
+    New,
+    Duplicate,
+    Rename,
+    Delete,
+}
+
+#[derive(Default)]
+pub struct ProfileSwitcher {
+    names: Vec<String>,
+    edit: Option<Edit>,
+    name: String,
+    status: String,
+}
+
+impl ProfileSwitcher {
+    pub fn reload(&mut self) {
+        self.names = profile::list();
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    fn apply_edit(&mut self, edit: &Edit, active: &str) -> Result<Option<String>, String> {
+        let name = self.name.trim().to_string();
+        if *edit != Edit::Delete && !profile::is_valid_name(&name) {
+            return Err(format!("Invalid profile name: {}", name));
+        }
+        if matches!(edit, Edit::New | Edit::Duplicate) && self.names.contains(&name) {
+            return Err(format!("Profile already exists: {}", name));
+        }
+        let result = match edit {
+            Edit::New => profile::store(&name, &Profile::default()).map(|_| Some(name)),
+            Edit::Duplicate => profile::store(&name, &profile::load(active)).map(|_| Some(name)),
+            Edit::Rename => profile::rename(active, &name).map(|_| {
+                profile::set_active(&name);
+                None
+            }),
+            Edit::Delete => profile::delete(active).map(|_| Some(profile::DEFAULT.to_string())),
+        };
+        result.map_err(|err| {
+            warn!("error editing profile: {}", err);
+            err.to_string()
+        })
+    }
+
+    /// Shows the profile switcher. Returns a profile to switch to.
+    pub fn show(&mut self, ui: &mut egui::Ui, active: &mut String) -> Option<String> {
+        let mut result = None;
+        ui.label("Profile:");
+        egui::ComboBox::from_id_salt("Profile")
+            .selected_text(active.as_str())
+            .show_ui(ui, |ui| {
+                for name in self.names.iter() {
+                    if ui.selectable_label(name == active, name).clicked() && name != active {
+                        result = Some(name.clone());
+                    }
+                }
+            });
+
+        match &self.edit {
+            None => {
+                if ui.button("➕ New").clicked() {
+                    self.edit = Some(Edit::New);
+                    self.name.clear();
+                }
+                if ui.button("⧉ Duplicate").clicked() {
+                    self.edit = Some(Edit::Duplicate);
+                    self.name = format!("{} copy", active);
+                }
+                let named = active != profile::DEFAULT;
+                if ui
+                    .add_enabled(named, egui::Button::new("✏ Rename"))
+                    .clicked()
+                {
+                    self.edit = Some(Edit::Rename);
+                    self.name = active.clone();
+                }
+                if ui
+                    .add_enabled(named, egui::Button::new("🗑 Delete"))
+                    .clicked()
+                {
+                    self.edit = Some(Edit::Delete);
+                }
+            }
+            Some(edit) => {
+                if *edit == Edit::Delete {
+                    ui.label(format!("Delete profile {}?", active));
+                } else {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut self.name);
+                }
+                if ui.button("✔ OK").clicked() {
+                    let edit = self.edit.take().unwrap_or(Edit::New);
+                    match self.apply_edit(&edit, active) {
+                        Ok(name) => {
+                            self.status.clear();
+                            if edit == Edit::Rename {
+                                *active = self.name.trim().to_string();
+                            }
+                            result = name;
+                        }
+                        Err(err) => self.status = err,
+                    }
+                    self.reload();
+                }
+                if ui.button("✖ Cancel").clicked() {
+                    self.edit = None;
+                }
+            }
+        }
+        if !self.status.is_empty() {
+            ui.colored_label(ui.visuals().warn_fg_color, &self.status);
+        }
+        result
+    }
+}
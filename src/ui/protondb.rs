@@ -0,0 +1,67 @@
+use crate::protondb::{self, AppReports, Tier};
+use crate::team;
+use eframe::egui;
+use log::debug;
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+type Imported = Result<BTreeMap<u32, AppReports>, String>;
+
+/// Imports a report dump in the background, dumps of all reports are hundreds of megabytes.
+#[derive(Default)]
+pub struct ProtonDbImport {
+    loading: Option<Receiver<Imported>>,
+}
+
+impl ProtonDbImport {
+    pub fn is_loading(&self) -> bool {
+        self.loading.is_some()
+    }
+
+    pub fn start(&mut self, ctx: &egui::Context) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("ProtonDB reports", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let result = protondb::import(&path, team::now())
+                .and_then(|protondb| {
+                    protondb::store(&protondb)?;
+                    debug!(
+                        "protondb: {} apps from {}",
+                        protondb.apps.len(),
+                        path.display()
+                    );
+                    Ok(protondb.by_appid())
+                })
+                .map_err(|err| format!("Error importing {}: {}", path.display(), err));
+            let _ = sender.send(result);
+            ctx.request_repaint();
+        });
+        self.loading = Some(receiver);
+    }
+
+    /// Returns the tiers once an import finished.
+    pub fn poll(&mut self) -> Option<Imported> {
+        let result = self.loading.as_ref()?.try_recv().ok()?;
+        self.loading = None;
+        Some(result)
+    }
+}
+
+/// The tier in the colors of the ProtonDB badges.
+pub fn tier_text(tier: Tier) -> egui::RichText {
+    let color = match tier {
+        Tier::Platinum => egui::Color32::from_rgb(180, 199, 220),
+        Tier::Gold => egui::Color32::from_rgb(207, 181, 59),
+        Tier::Silver => egui::Color32::from_rgb(166, 166, 166),
+        Tier::Bronze => egui::Color32::from_rgb(205, 127, 50),
+        Tier::Borked => egui::Color32::from_rgb(255, 0, 0),
+    };
+    egui::RichText::new(tier.to_string()).color(color)
+}
@@ -0,0 +1,281 @@
+use crate::App;
+use crate::fsutil;
+use crate::shadercache::{self, ShaderCache};
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use log::warn;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// Shader caches of all libraries, measured in the background whenever the apps are read.
+#[derive(Default)]
+pub struct ShaderCacheWindow {
+    pub open: bool,
+    caches: Vec<ShaderCache>,
+    scanned: bool,
+    loading: Option<Receiver<Vec<ShaderCache>>>,
+    selected: BTreeSet<(PathBuf, u32)>,
+    confirm_delete: bool,
+    /// The number of deleted caches and the errors, sent when the deletion finished
+    delete: Option<Receiver<(usize, Vec<String>)>>,
+    status: String,
+}
+
+impl ShaderCacheWindow {
+    pub fn needs_scan(&self) -> bool {
+        !self.scanned
+    }
+
+    /// Measures the caches again on the next frame.
+    pub fn invalidate(&mut self) {
+        self.scanned = false;
+    }
+
+    pub fn scan(&mut self, ctx: &egui::Context, libraries: Vec<PathBuf>) {
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ = sender.send(shadercache::scan(&libraries));
+            ctx.request_repaint();
+        });
+        self.scanned = true;
+        self.loading = Some(receiver);
+    }
+
+    /// Returns the sizes per app once a scan finished.
+    pub fn poll(&mut self, apps: &BTreeMap<u32, App>) -> Option<BTreeMap<u32, u64>> {
+        let caches = self.loading.as_ref()?.try_recv().ok()?;
+        self.loading = None;
+        self.selected.retain(|(library, appid)| {
+            caches
+                .iter()
+                .any(|cache| cache.appid == *appid && cache.library == *library)
+        });
+        self.caches = caches;
+        let installed = apps
+            .iter()
+            .map(|(appid, app)| (*appid, app.library.clone()))
+            .collect();
+        Some(shadercache::sizes(&self.caches, &installed))
+    }
+
+    fn start_delete(&mut self, ctx: &egui::Context) {
+        let caches: Vec<ShaderCache> = self
+            .caches
+            .iter()
+            .filter(|cache| {
+                self.selected
+                    .contains(&(cache.library.clone(), cache.appid))
+            })
+            .cloned()
+            .collect();
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        self.status = format!("Deleting {} shader cache(s)…", caches.len());
+        thread::spawn(move || {
+            let mut deleted = 0;
+            let mut errors = vec![];
+            for cache in caches.iter() {
+                match shadercache::delete(cache) {
+                    Ok(()) => deleted += 1,
+                    Err(err) => {
+                        warn!("error deleting shader cache: {}", err);
+                        errors.push(format!("{}: {}", cache.appid, err));
+                    }
+                }
+            }
+            let _ = sender.send((deleted, errors));
+            ctx.request_repaint();
+        });
+        self.delete = Some(receiver);
+        self.selected.clear();
+    }
+
+    /// Returns whether a finished deletion removed any cache.
+    fn poll_delete(&mut self) -> bool {
+        let Some((deleted, errors)) = self
+            .delete
+            .as_ref()
+            .and_then(|receiver| receiver.try_recv().ok())
+        else {
+            return false;
+        };
+        self.delete = None;
+        self.status = if errors.is_empty() {
+            format!("Deleted {} shader cache(s)", deleted)
+        } else {
+            format!("Deleted {}, failed: {}", deleted, errors.join(", "))
+        };
+        deleted > 0
+    }
+
+    /// Lists the caches with the app names, `selection` being the apps selected in the table.
+    /// Returns whether any cache was deleted, for the sizes to be measured again.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        apps: &BTreeMap<u32, App>,
+        selection: &BTreeSet<u32>,
+    ) -> bool {
+        let deleted = self.poll_delete();
+        let mut open = self.open;
+        egui::Window::new("💾 Shader caches")
+            .id(egui::Id::new("ShaderCaches"))
+            .open(&mut open)
+            .default_size([500.0, 400.0])
+            .show(ctx, |ui| {
+                if self.loading.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Measuring shader caches…");
+                    });
+                }
+                if self.delete.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(&self.status);
+                    });
+                }
+                for (library, total) in shadercache::totals(&self.caches).iter() {
+                    ui.horizontal(|ui| {
+                        ui.monospace(library.to_string_lossy());
+                        ui.label(fsutil::format_size(*total));
+                    });
+                }
+                ui.separator();
+
+                let is_installed = |cache: &ShaderCache| {
+                    apps.get(&cache.appid)
+                        .is_some_and(|app| app.library == cache.library)
+                };
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("Select:");
+                    if ui.button("Uninstalled").clicked() {
+                        self.selected = self
+                            .caches
+                            .iter()
+                            .filter(|cache| !is_installed(cache))
+                            .map(|cache| (cache.library.clone(), cache.appid))
+                            .collect();
+                    }
+                    if ui
+                        .add_enabled(!selection.is_empty(), egui::Button::new("Selected apps"))
+                        .on_hover_text("Apps selected in the table with Ctrl+click")
+                        .clicked()
+                    {
+                        self.selected = self
+                            .caches
+                            .iter()
+                            .filter(|cache| selection.contains(&cache.appid) && is_installed(cache))
+                            .map(|cache| (cache.library.clone(), cache.appid))
+                            .collect();
+                    }
+                    if ui.button("None").clicked() {
+                        self.selected.clear();
+                    }
+                    let size: u64 = self
+                        .caches
+                        .iter()
+                        .filter(|cache| {
+                            self.selected
+                                .contains(&(cache.library.clone(), cache.appid))
+                        })
+                        .map(|cache| cache.size)
+                        .sum();
+                    if ui
+                        .add_enabled(
+                            !self.selected.is_empty() && self.delete.is_none(),
+                            egui::Button::new(format!(
+                                "🗑 Delete {} ({})",
+                                self.selected.len(),
+                                fsutil::format_size(size)
+                            )),
+                        )
+                        .clicked()
+                    {
+                        self.confirm_delete = true;
+                    }
+                });
+                if !self.status.is_empty() && self.delete.is_none() {
+                    ui.label(&self.status);
+                }
+                ui.separator();
+
+                TableBuilder::new(ui)
+                    .striped(true)
+                    .column(Column::auto())
+                    .column(Column::auto().at_least(70.0))
+                    .column(Column::remainder().clip(true))
+                    .column(Column::auto().at_least(70.0))
+                    .header(20.0, |mut header| {
+                        header.col(|_| {});
+                        header.col(|ui| {
+                            ui.strong("App ID");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Name");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Size");
+                        });
+                    })
+                    .body(|body| {
+                        body.rows(20.0, self.caches.len(), |mut row| {
+                            let cache = &self.caches[row.index()];
+                            let key = (cache.library.clone(), cache.appid);
+                            row.col(|ui| {
+                                let mut checked = self.selected.contains(&key);
+                                if ui.checkbox(&mut checked, "").changed() {
+                                    if checked {
+                                        self.selected.insert(key.clone());
+                                    } else {
+                                        self.selected.remove(&key);
+                                    }
+                                }
+                            });
+                            row.col(|ui| {
+                                ui.label(cache.appid.to_string());
+                            });
+                            row.col(|ui| {
+                                match apps.get(&cache.appid) {
+                                    Some(app) if is_installed(cache) => ui.label(&app.name),
+                                    _ => ui.weak("uninstalled"),
+                                };
+                            });
+                            row.col(|ui| {
+                                ui.label(fsutil::format_size(cache.size));
+                            });
+                        });
+                    });
+            });
+
+        if self.confirm_delete {
+            let modal =
+                egui::Modal::new(egui::Id::new("confirm_delete_shader_caches")).show(ctx, |ui| {
+                    ui.heading("Delete shader caches");
+                    ui.label(format!(
+                        "Delete {} shader cache(s)? Steam downloads or rebuilds them the next \
+                         time the apps run.",
+                        self.selected.len()
+                    ));
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("🗑 Delete").clicked() {
+                            self.start_delete(ctx);
+                            self.confirm_delete = false;
+                        }
+                        if ui.button("✖ Cancel").clicked() {
+                            self.confirm_delete = false;
+                        }
+                    });
+                });
+            if modal.should_close() {
+                self.confirm_delete = false;
+            }
+        }
+        self.open = open;
+        deleted
+    }
+}
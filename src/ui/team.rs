@@ -0,0 +1,330 @@
+use crate::team::{self, Entry, Status, SyncState, TeamProfile};
+use crate::{App, config};
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use log::{debug, warn};
+use std::collections::BTreeMap;
+
+#[derive(Default)]
+pub struct TeamWindow {
+    pub open: bool,
+    team_dir: Option<String>,
+    team: TeamProfile,
+    contributions: BTreeMap<String, TeamProfile>,
+    sync: SyncState,
+    only_differences: bool,
+    status: String,
+}
+
+/// Local state that team entries are pulled into.
+pub struct Local<'a> {
+    pub all_launch_options: &'a mut BTreeMap<u32, String>,
+    pub notes: &'a mut BTreeMap<u32, String>,
+    pub tags: &'a mut BTreeMap<u32, Vec<String>>,
+}
+
+fn format_time(seconds: u64) -> String {
+    gix::date::Time::new(seconds as i64, 0).format(gix::date::time::format::ISO8601)
+}
+
+impl TeamWindow {
+    pub fn load(&mut self) {
+        let config = config::load();
+        self.team_dir = config.team_dir;
+        self.sync = team::load_sync();
+        self.only_differences = true;
+        self.reload();
+        self.open = true;
+    }
+
+    fn reload(&mut self) {
+        self.team = TeamProfile::default();
+        self.contributions.clear();
+        self.status.clear();
+        if let Some(team_dir) = &self.team_dir {
+            match team::read_team_profile(team_dir) {
+                Ok(team) => self.team = team,
+                Err(err) => {
+                    warn!("error reading team profile: {}", err);
+                    self.status = format!("Error reading team profile: {}", err);
+                }
+            }
+            match team::read_contributions(team_dir) {
+                Ok(contributions) => self.contributions = contributions,
+                Err(err) => warn!("error reading contributions: {}", err),
+            }
+        }
+        debug!("team profile: {:?}", self.team);
+    }
+
+    fn store_sync(&self) {
+        if let Err(err) = team::store_sync(&self.sync) {
+            warn!("error storing team sync state: {}", err);
+        }
+    }
+
+    fn pull(&mut self, appids: &[u32], local: &mut Local) {
+        for appid in appids.iter() {
+            if let Some(entry) = self.team.apps.get(&appid.to_string()) {
+                local
+                    .all_launch_options
+                    .insert(*appid, entry.launch_options.clone());
+                if let Some(notes) = &entry.notes {
+                    local.notes.insert(*appid, notes.clone());
+                }
+                if let Some(tags) = &entry.tags {
+                    local.tags.insert(*appid, tags.clone());
+                }
+                self.sync.synced.insert(appid.to_string(), entry.clone());
+            }
+        }
+        self.store_sync();
+        self.status = format!("Pulled {} app(s), save to apply", appids.len());
+    }
+
+    fn propose(&mut self, entries: BTreeMap<u32, Entry>) {
+        let Some(team_dir) = &self.team_dir else {
+            return;
+        };
+        let count = entries.len();
+        match team::propose(team_dir, entries, &self.team) {
+            Ok(()) => {
+                self.status = format!("Proposed {} app(s) as {}", count, team::user_name());
+                self.reload();
+            }
+            Err(err) => {
+                warn!("error writing contribution: {}", err);
+                self.status = format!("Error writing contribution: {}", err);
+            }
+        }
+    }
+
+    fn proposals(&self, appid: &str) -> Vec<(&String, &Entry)> {
+        self.contributions
+            .iter()
+            .filter_map(|(user, contribution)| {
+                contribution.apps.get(appid).map(|entry| (user, entry))
+            })
+            .collect()
+    }
+
+    /// Returns whether anything was pulled into the local launch options, notes or tags.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        apps: &BTreeMap<u32, App>,
+        local: &mut Local,
+    ) -> bool {
+        let mut pulled = false;
+        let mut open = self.open;
+        egui::Window::new("🤝 Team")
+            .open(&mut open)
+            .default_size([800.0, 400.0])
+            .show(ctx, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    if ui.button("🗁 Team directory…").clicked()
+                        && let Some(path) = rfd::FileDialog::new().pick_folder()
+                    {
+                        let team_dir = path.to_string_lossy().to_string();
+                        let mut config = config::load();
+                        config.team_dir = Some(team_dir.clone());
+                        config::store(config);
+                        self.team_dir = Some(team_dir);
+                        self.reload();
+                    }
+                    if ui.button("⟳ Refresh").clicked() {
+                        self.reload();
+                    }
+                    if let Some(team_dir) = &self.team_dir {
+                        ui.monospace(team_dir);
+                    }
+                });
+
+                if self.team_dir.is_none() {
+                    ui.label("Pick a shared directory containing team.toml");
+                    return;
+                }
+
+                let all_launch_options = &*local.all_launch_options;
+                let launch_options = |appid: &u32| {
+                    all_launch_options
+                        .get(appid)
+                        .or_else(|| apps.get(appid).map(|app| &app.launch_options))
+                        .cloned()
+                        .unwrap_or_default()
+                };
+                let mut statuses: Vec<(u32, Status)> = vec![];
+                for appid in apps.keys() {
+                    let key = appid.to_string();
+                    let team = self.team.apps.get(&key);
+                    let status =
+                        team::status(&launch_options(appid), team, self.sync.synced.get(&key));
+                    // Remember matching team entries as the new baseline
+                    if status == Status::Same
+                        && let Some(team) = team
+                        && self.sync.synced.get(&key) != Some(team)
+                    {
+                        self.sync.synced.insert(key, team.clone());
+                        self.store_sync();
+                    }
+                    if !self.only_differences || status != Status::Same {
+                        statuses.push((*appid, status));
+                    }
+                }
+                let select = |wanted: &[Status]| -> Vec<u32> {
+                    statuses
+                        .iter()
+                        .filter(|(_, status)| wanted.contains(status))
+                        .map(|(appid, _)| *appid)
+                        .collect()
+                };
+                let behind = select(&[Status::Behind]);
+                let ahead = select(&[Status::Ahead, Status::Missing]);
+                let conflicts = select(&[Status::Conflict]).len();
+                let mut pull = vec![];
+                let mut propose = vec![];
+
+                ui.horizontal_wrapped(|ui| {
+                    if ui
+                        .add_enabled(!behind.is_empty(), egui::Button::new("⬇ Pull team options"))
+                        .clicked()
+                    {
+                        pull = behind.clone();
+                    }
+                    if ui
+                        .add_enabled(
+                            !ahead.is_empty(),
+                            egui::Button::new("⬆ Propose local options"),
+                        )
+                        .clicked()
+                    {
+                        propose = ahead.clone();
+                    }
+                    ui.checkbox(&mut self.only_differences, "Only differences");
+                    if conflicts > 0 {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("{} conflict(s), resolve per app", conflicts),
+                        );
+                    }
+                    ui.label(&self.status);
+                });
+
+                ui.separator();
+
+                TableBuilder::new(ui)
+                    .resizable(true)
+                    .striped(true)
+                    .column(Column::initial(200.0).clip(true))
+                    .column(Column::initial(200.0).clip(true))
+                    .column(Column::initial(200.0).clip(true))
+                    .column(Column::initial(80.0))
+                    .column(Column::remainder().at_least(160.0))
+                    .header(20.0, |mut header| {
+                        header.col(|ui| {
+                            ui.strong(" Steam Apps");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Local");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Team");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Status");
+                        });
+                        header.col(|_| {});
+                    })
+                    .body(|body| {
+                        body.rows(20.0, statuses.len(), |mut row| {
+                            let (appid, status) = statuses[row.index()];
+                            let key = appid.to_string();
+                            let team = self.team.apps.get(&key);
+                            row.col(|ui| {
+                                ui.label(apps.get(&appid).map_or("", |app| app.name.as_str()));
+                            });
+                            row.col(|ui| {
+                                let launch_options = launch_options(&appid);
+                                ui.monospace(&launch_options).on_hover_text(&launch_options);
+                            });
+                            row.col(|ui| {
+                                if let Some(team) = team {
+                                    ui.monospace(&team.launch_options).on_hover_text(format!(
+                                        "{}\n{} by {}",
+                                        team.launch_options,
+                                        format_time(team.modified),
+                                        team.author.as_deref().unwrap_or("unknown")
+                                    ));
+                                }
+                            });
+                            row.col(|ui| {
+                                let text = format!("{:?}", status);
+                                let response = if status == Status::Conflict {
+                                    ui.colored_label(ui.visuals().warn_fg_color, text)
+                                } else {
+                                    ui.label(text)
+                                };
+                                let proposals = self.proposals(&key);
+                                if !proposals.is_empty() {
+                                    response.on_hover_ui(|ui| {
+                                        ui.label("Proposed:");
+                                        for (user, entry) in proposals.iter() {
+                                            let outdated = match (entry.base, team) {
+                                                (Some(base), Some(team)) => base < team.modified,
+                                                (None, Some(_)) => true,
+                                                _ => false,
+                                            };
+                                            ui.monospace(format!(
+                                                "{} ({}{}): {}",
+                                                user,
+                                                format_time(entry.modified),
+                                                if outdated { ", outdated" } else { "" },
+                                                entry.launch_options
+                                            ));
+                                        }
+                                    });
+                                }
+                            });
+                            row.col(|ui| {
+                                if ui
+                                    .add_enabled(team.is_some(), egui::Button::new("⬇ Pull"))
+                                    .clicked()
+                                {
+                                    pull = vec![appid];
+                                }
+                                if ui
+                                    .add_enabled(
+                                        status != Status::Behind,
+                                        egui::Button::new("⬆ Propose"),
+                                    )
+                                    .clicked()
+                                {
+                                    propose = vec![appid];
+                                }
+                            });
+                        });
+                    });
+                if !propose.is_empty() {
+                    let entries: BTreeMap<u32, Entry> = propose
+                        .iter()
+                        .map(|appid| {
+                            let entry = Entry {
+                                launch_options: launch_options(appid),
+                                notes: local.notes.get(appid).cloned(),
+                                tags: local.tags.get(appid).cloned(),
+                                ..Default::default()
+                            };
+                            (*appid, entry)
+                        })
+                        .collect();
+                    self.propose(entries);
+                }
+                if !pull.is_empty() {
+                    self.pull(&pull, local);
+                    pulled = true;
+                }
+            });
+        self.open = open;
+        pulled
+    }
+}
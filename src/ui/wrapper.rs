@@ -0,0 +1,207 @@
+use crate::hooks::{self, Hooks};
+use crate::launch_options;
+use crate::wrapper::{self, Wrapper};
+use eframe::egui;
+use log::warn;
+
+#[derive(Default)]
+pub struct WrapperWindow {
+    pub open: bool,
+    appid: u32,
+    name: String,
+    wrapper: Wrapper,
+    /// The app's hooks from the profile, run by the script
+    hooks: Hooks,
+    /// Whether the launch options already run the script
+    wrapped: bool,
+    wrappers: String,
+    args: String,
+    /// Why the launch options cannot be moved into a script
+    refused: Option<String>,
+    error: Option<String>,
+}
+
+impl WrapperWindow {
+    /// Edits the app's script, or prepares one from its launch options.
+    pub fn load(&mut self, appid: u32, name: &str, launch_options: &str, hooks: Option<&Hooks>) {
+        self.appid = appid;
+        self.name = name.to_string();
+        self.hooks = hooks.cloned().unwrap_or_default();
+        self.error = None;
+        self.refused = None;
+        self.wrapped = wrapper::is_wrapped(launch_options, appid);
+        let stored = if self.wrapped {
+            wrapper::read(appid).unwrap_or_else(|err| {
+                warn!("error reading wrapper: {}", err);
+                self.error = Some(err.to_string());
+                None
+            })
+        } else {
+            None
+        };
+        // The script runs the hooks itself
+        self.wrapper = match stored {
+            Some(stored) => stored,
+            None => Wrapper::from_launch_options(&hooks::apply(launch_options, None))
+                .unwrap_or_else(|err| {
+                    self.refused = Some(err);
+                    Wrapper::default()
+                }),
+        };
+        self.wrappers = self.wrapper.wrappers.join(" ");
+        self.args = self.wrapper.args.join(" ");
+        self.open = true;
+    }
+
+    fn edited(&self) -> Wrapper {
+        let mut wrapper = self.wrapper.clone();
+        wrapper
+            .env
+            .retain(|(name, _)| launch_options::is_env_name(name));
+        wrapper.wrappers = launch_options::split_written(&self.wrappers);
+        wrapper.args = launch_options::split_written(&self.args);
+        wrapper
+    }
+
+    /// Why the edited script cannot be saved.
+    fn problem(&self, wrapper: &Wrapper) -> Option<String> {
+        if let Some(operator) = wrapper
+            .wrappers
+            .iter()
+            .chain(wrapper.args.iter())
+            .find(|word| launch_options::is_operator(word))
+        {
+            return Some(format!(
+                "The script runs a single command, remove {}",
+                operator
+            ));
+        }
+        self.refused.clone()
+    }
+
+    /// Returns the app with its new launch options.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<(u32, String)> {
+        let mut result = None;
+        let mut open = self.open;
+        egui::Window::new(format!("📜 Wrapper script: {}", self.name))
+            .id(egui::Id::new("Wrapper"))
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                if let Some(err) = &self.error {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", err));
+                }
+                if let Ok(script) = wrapper::script_path(self.appid) {
+                    ui.horizontal(|ui| {
+                        ui.label("Script:");
+                        ui.monospace(script.to_string_lossy());
+                    });
+                }
+                ui.separator();
+
+                ui.strong("Environment");
+                let mut removed = None;
+                egui::Grid::new("WrapperEnv").num_columns(3).show(ui, |ui| {
+                    for (i, (name, value)) in self.wrapper.env.iter_mut().enumerate() {
+                        let mut response = ui.add(
+                            egui::TextEdit::singleline(name)
+                                .desired_width(160.0)
+                                .hint_text("NAME"),
+                        );
+                        if !name.is_empty() && !launch_options::is_env_name(name) {
+                            response = response.on_hover_text("⚠ Not a variable name");
+                            ui.painter().rect_stroke(
+                                response.rect,
+                                2.0,
+                                egui::Stroke::new(1.0, ui.visuals().warn_fg_color),
+                                egui::StrokeKind::Outside,
+                            );
+                        }
+                        ui.add(
+                            egui::TextEdit::singleline(value)
+                                .desired_width(220.0)
+                                .hint_text("shell syntax, quote spaces"),
+                        );
+                        if ui.small_button("🗑").clicked() {
+                            removed = Some(i);
+                        }
+                        ui.end_row();
+                    }
+                });
+                if let Some(i) = removed {
+                    self.wrapper.env.remove(i);
+                }
+                if ui.button("➕ Variable").clicked() {
+                    self.wrapper.env.push(Default::default());
+                }
+                ui.separator();
+
+                egui::Grid::new("WrapperCommand")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Before the game:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.wrappers)
+                                .hint_text("gamemoderun mangohud"),
+                        );
+                        ui.end_row();
+                        ui.label("Game arguments:");
+                        ui.add(egui::TextEdit::singleline(&mut self.args).hint_text("-novid"));
+                        ui.end_row();
+                    });
+
+                if !self.hooks.is_empty() {
+                    ui.label("🔗 Runs the app's hooks before and after the game");
+                }
+                let log = wrapper::log_path(self.appid)
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default();
+                ui.checkbox(&mut self.wrapper.log, "Log output")
+                    .on_hover_text(log);
+
+                let edited = self.edited();
+                egui::CollapsingHeader::new("Preview").show(ui, |ui| {
+                    let model = wrapper::model_path(self.appid).unwrap_or_default();
+                    let log = wrapper::log_path(self.appid).unwrap_or_default();
+                    let hooks_log = hooks::log_path(self.appid).unwrap_or_default();
+                    ui.monospace(edited.script(&model, &log, &self.hooks, &hooks_log));
+                });
+                ui.separator();
+
+                let problem = self.problem(&edited);
+                if let Some(problem) = &problem {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", problem));
+                }
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(problem.is_none(), egui::Button::new("💾 Save and use"))
+                        .clicked()
+                    {
+                        match wrapper::write(self.appid, &edited, &self.hooks) {
+                            Ok(script) => {
+                                result = Some((self.appid, wrapper::launch_options(&script)));
+                                self.open = false;
+                            }
+                            Err(err) => {
+                                warn!("error writing wrapper: {}", err);
+                                self.error = Some(err.to_string());
+                            }
+                        }
+                    }
+                    if ui
+                        .add_enabled(self.wrapped, egui::Button::new("⮌ Back to launch options"))
+                        .on_hover_text("Keeps the script, logging is not used")
+                        .clicked()
+                    {
+                        result = Some((self.appid, edited.to_launch_options()));
+                        self.open = false;
+                    }
+                    if ui.button("✖ Cancel").clicked() {
+                        self.open = false;
+                    }
+                });
+            });
+        self.open = open && self.open;
+        result
+    }
+}
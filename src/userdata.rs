@@ -0,0 +1,104 @@
+use crate::localconfig_vdf;
+use log::{debug, warn};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub struct Account {
+    pub id: u32,
+    pub localconfig_vdf_path: String,
+    pub launch_options: BTreeMap<u32, String>,
+}
+
+fn userdata_dir(localconfig_vdf_path: &String) -> PathBuf {
+    let mut path = PathBuf::from(localconfig_vdf_path);
+    path.pop();
+    path.pop();
+    path.pop();
+    path
+}
+
+pub fn read_accounts(localconfig_vdf_path: &String) -> Result<Vec<Account>, Box<dyn Error>> {
+    let mut result = vec![];
+    for entry in fs::read_dir(userdata_dir(localconfig_vdf_path))? {
+        let path = entry?.path();
+        let id = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => match name.parse::<u32>() {
+                Ok(id) => id,
+                Err(_) => continue,
+            },
+            None => continue,
+        };
+        let localconfig_vdf_path = path.join("config").join("localconfig.vdf");
+        if !localconfig_vdf_path.is_file() {
+            debug!("no localconfig.vdf for account: {}", id);
+            continue;
+        }
+        let localconfig_vdf_path = localconfig_vdf_path.to_string_lossy().to_string();
+        match localconfig_vdf::read_launch_options(&localconfig_vdf_path) {
+            Ok(launch_options) => result.push(Account {
+                id,
+                localconfig_vdf_path,
+                launch_options,
+            }),
+            Err(err) => warn!("error reading {}: {}", localconfig_vdf_path, err),
+        }
+    }
+    result.sort_by_key(|account| account.id);
+    Ok(result)
+}
+
+impl Account {
+    pub fn get(&self, appid: &u32) -> &str {
+        self.launch_options.get(appid).map_or("", |s| s.as_str())
+    }
+}
+
+pub fn differs(accounts: &[Account], appid: &u32) -> bool {
+    accounts
+        .windows(2)
+        .any(|pair| pair[0].get(appid).trim() != pair[1].get(appid).trim())
+}
+
+pub fn copy_launch_options(
+    source: &Account,
+    target: &mut Account,
+    appids: &[u32],
+) -> Result<BTreeMap<u32, String>, Box<dyn Error>> {
+    let mut copied = BTreeMap::new();
+    for appid in appids.iter() {
+        copied.insert(*appid, source.get(appid).to_string());
+    }
+    crate::backup_file(&target.localconfig_vdf_path, ".bak")?;
+    localconfig_vdf::write_launch_options(&target.localconfig_vdf_path, &copied)?;
+    target.launch_options = localconfig_vdf::read_launch_options(&target.localconfig_vdf_path)?;
+    debug!(
+        "copied {} launch options from {} to {}",
+        copied.len(),
+        source.id,
+        target.id
+    );
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_accounts() {
+        let accounts =
+            read_accounts(&"./tests/userdata/11111111/config/localconfig.vdf".to_string()).unwrap();
+        assert_eq!(
+            accounts
+                .iter()
+                .map(|account| account.id)
+                .collect::<Vec<u32>>(),
+            vec![11111111, 22222222]
+        );
+        assert!(!differs(&accounts, &1111111));
+        assert!(differs(&accounts, &9999999));
+    }
+}
@@ -0,0 +1,247 @@
+//! Launch options moved into a generated shell script, so that long ones stay readable. The
+//! model is kept next to the script and the script is regenerated from it on save.
+
+use crate::config;
+use crate::hooks::{self, Hooks};
+use crate::launch_options::{self, COMMAND, quote};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The parts of the launch options, each word as it was written so that the script reads it
+/// the same way Steam's shell does.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Wrapper {
+    pub env: Vec<(String, String)>,
+    /// Commands before the game
+    pub wrappers: Vec<String>,
+    /// Arguments after the game
+    pub args: Vec<String>,
+    /// Appends the output of the game to a log file
+    pub log: bool,
+}
+
+const DIR: &str = "wrappers";
+
+pub fn script_path(appid: u32) -> Result<PathBuf, Box<dyn Error>> {
+    config::managed_file(DIR, appid, "sh")
+}
+
+pub fn log_path(appid: u32) -> Result<PathBuf, Box<dyn Error>> {
+    config::managed_file(DIR, appid, "log")
+}
+
+/// The model the script is generated from.
+pub fn model_path(appid: u32) -> Result<PathBuf, Box<dyn Error>> {
+    config::managed_file(DIR, appid, "toml")
+}
+
+impl Wrapper {
+    /// Fails on launch options with operators such as `;` or `|`, since the script runs a single
+    /// command.
+    pub fn from_launch_options(text: &str) -> Result<Wrapper, String> {
+        let launch_options = launch_options::parse_written(text);
+        if let Some(operator) = launch_options
+            .wrappers
+            .iter()
+            .chain(launch_options.args.iter())
+            .find(|word| launch_options::is_operator(word))
+        {
+            return Err(format!(
+                "The launch options run more than the game with {}, edit them directly",
+                operator
+            ));
+        }
+        Ok(Wrapper {
+            env: launch_options.env,
+            wrappers: launch_options.wrappers,
+            args: launch_options.args,
+            ..Default::default()
+        })
+    }
+
+    /// The launch options without the script. Logging is lost.
+    pub fn to_launch_options(&self) -> String {
+        let mut words: Vec<String> = self
+            .env
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        words.extend(self.wrappers.iter().cloned());
+        if !self.env.is_empty() || !self.wrappers.is_empty() {
+            words.push(COMMAND.to_string());
+        }
+        words.extend(self.args.iter().cloned());
+        words.join(" ")
+    }
+
+    /// The script, which runs the game command it is given as arguments between the app's hooks.
+    pub fn script(&self, model: &Path, log: &Path, hooks: &Hooks, hooks_log: &Path) -> String {
+        let mut lines = vec![
+            "#!/bin/sh".to_string(),
+            format!(
+                "# Generated by {} from {}, changes here are overwritten",
+                crate::consts::APP_NAME,
+                model.display()
+            ),
+        ];
+        if self.log {
+            lines.push(format!("exec >>{} 2>&1", quote(&log.to_string_lossy())));
+            lines.push("echo \"--- $(date) $*\"".to_string());
+        }
+        for (name, value) in self.env.iter() {
+            lines.push(format!("export {}={}", name, value));
+        }
+        let mut command = self.wrappers.clone();
+        command.push("\"$@\"".to_string());
+        command.extend(self.args.iter().cloned());
+        lines.extend(hooks.lines(&command.join(" "), hooks_log));
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Launch options that run the game through the script.
+pub fn launch_options(script: &Path) -> String {
+    format!("{} {}", quote(&script.to_string_lossy()), COMMAND)
+}
+
+/// Whether the launch options run the app's script.
+pub fn is_wrapped(text: &str, appid: u32) -> bool {
+    script_path(appid).is_ok_and(|script| {
+        launch_options::parse(text)
+            .wrappers
+            .iter()
+            .any(|word| Path::new(word) == script)
+    })
+}
+
+pub fn read(appid: u32) -> Result<Option<Wrapper>, Box<dyn Error>> {
+    let path = model_path(appid)?;
+    if !path.is_file() {
+        return Ok(None);
+    }
+    Ok(Some(toml::from_str(&fs::read_to_string(path)?)?))
+}
+
+/// Writes the model and generates the executable script from it, returning the script.
+pub fn write(appid: u32, wrapper: &Wrapper, hooks: &Hooks) -> Result<PathBuf, Box<dyn Error>> {
+    let model = model_path(appid)?;
+    let script = script_path(appid)?;
+    fs::create_dir_all(config::managed_dir(DIR)?)?;
+    fs::write(&model, toml::to_string(wrapper)?)?;
+    fs::write(
+        &script,
+        wrapper.script(&model, &log_path(appid)?, hooks, &hooks::log_path(appid)?),
+    )?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755))?;
+    }
+    debug!("wrapper script written: {}", script.display());
+    Ok(script)
+}
+
+/// Generates the script again from its model and the app's current hooks.
+pub fn regenerate(appid: u32, hooks: &Hooks) -> Result<(), Box<dyn Error>> {
+    if let Some(wrapper) = read(appid)? {
+        write(appid, &wrapper, hooks)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script() {
+        let mut wrapper = Wrapper::from_launch_options(
+            "PROTON_LOG=1 WINEDLLOVERRIDES=\"dinput8=n,b\" gamemoderun %command% -novid",
+        )
+        .unwrap();
+        assert_eq!(
+            wrapper.to_launch_options(),
+            "PROTON_LOG=1 WINEDLLOVERRIDES=\"dinput8=n,b\" gamemoderun %command% -novid"
+        );
+        let model = Path::new("/wrappers/1111111.toml");
+        let log = Path::new("/wrappers/1111111.log");
+        let hooks_log = Path::new("/hooks/1111111.log");
+        assert_eq!(
+            wrapper.script(model, log, &Hooks::default(), hooks_log),
+            "#!/bin/sh\n\
+             # Generated by Steam OptionX from /wrappers/1111111.toml, changes here are overwritten\n\
+             export PROTON_LOG=1\n\
+             export WINEDLLOVERRIDES=\"dinput8=n,b\"\n\
+             exec gamemoderun \"$@\" -novid\n"
+        );
+
+        wrapper.env.truncate(1);
+        wrapper.log = true;
+        let hooks = Hooks {
+            pre: "pactl set-default-sink game".to_string(),
+            post: "pactl set-default-sink desk\n".to_string(),
+        };
+        assert_eq!(
+            wrapper.script(model, log, &hooks, hooks_log),
+            "#!/bin/sh\n\
+             # Generated by Steam OptionX from /wrappers/1111111.toml, changes here are overwritten\n\
+             exec >>/wrappers/1111111.log 2>&1\n\
+             echo \"--- $(date) $*\"\n\
+             export PROTON_LOG=1\n\
+             log=/hooks/1111111.log\n\
+             echo \"--- pre $(date)\" >>\"$log\"\n\
+             {\n\
+             pactl set-default-sink game\n\
+             } >>\"$log\" 2>&1\n\
+             gamemoderun \"$@\" -novid\n\
+             status=$?\n\
+             echo \"--- post $(date) exit $status\" >>\"$log\"\n\
+             {\n\
+             pactl set-default-sink desk\n\
+             } >>\"$log\" 2>&1\n\
+             exit $status\n"
+        );
+        assert_eq!(
+            toml::from_str::<Wrapper>(&toml::to_string(&wrapper).unwrap()).unwrap(),
+            wrapper
+        );
+    }
+
+    #[test]
+    fn test_shell_words() {
+        let text = "DXVK_HUD=$FOO ~/bin/x.sh %command% -log=\"$HOME/my log\"";
+        let wrapper = Wrapper::from_launch_options(text).unwrap();
+        assert_eq!(wrapper.to_launch_options(), text);
+        let script = wrapper.script(
+            Path::new("/wrappers/1111111.toml"),
+            Path::new("/wrappers/1111111.log"),
+            &Hooks::default(),
+            Path::new("/hooks/1111111.log"),
+        );
+        assert!(
+            script
+                .ends_with("export DXVK_HUD=$FOO\nexec ~/bin/x.sh \"$@\" -log=\"$HOME/my log\"\n")
+        );
+        assert!(Wrapper::from_launch_options("gamemoderun %command% | tee log").is_err());
+        assert!(Wrapper::from_launch_options("%command%; echo done").is_err());
+    }
+
+    #[test]
+    fn test_launch_options() {
+        assert_eq!(
+            launch_options(Path::new("/home/user/My Games/1.sh")),
+            "'/home/user/My Games/1.sh' %command%"
+        );
+        let script = script_path(1111111).unwrap();
+        assert!(is_wrapped(&launch_options(&script), 1111111));
+        assert!(!is_wrapped(&launch_options(&script), 2222222));
+        let hooks = hooks::script_path(1111111).unwrap();
+        assert!(is_wrapped(
+            &hooks::apply(&launch_options(&script), Some(&hooks)),
+            1111111
+        ));
+    }
+}